@@ -0,0 +1,90 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Shows the binary clock inside an ordinary COSMIC application, next to a button that switches
+//! between display modes.
+//!
+//! Run with `cargo run --example embed`.
+
+use chrono::{DateTime, FixedOffset, Local};
+use cosmic::iced::widget::canvas;
+use cosmic::iced::{Length, Size, Subscription};
+use cosmic::prelude::*;
+use cosmic::widget;
+use example::clock::BinaryClock;
+use example::config::DisplayMode;
+
+struct Embed {
+    core: cosmic::Core,
+    mode: DisplayMode,
+    current_time: DateTime<FixedOffset>,
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    Tick,
+    NextMode,
+}
+
+impl cosmic::Application for Embed {
+    type Executor = cosmic::executor::Default;
+    type Flags = ();
+    type Message = Message;
+
+    const APP_ID: &'static str = "com.github.vancha.BinaryClockEmbed";
+
+    fn core(&self) -> &cosmic::Core {
+        &self.core
+    }
+
+    fn core_mut(&mut self) -> &mut cosmic::Core {
+        &mut self.core
+    }
+
+    fn init(core: cosmic::Core, _flags: Self::Flags) -> (Self, Task<cosmic::Action<Self::Message>>) {
+        let app = Embed {
+            core,
+            mode: DisplayMode::default(),
+            current_time: Local::now().fixed_offset(),
+        };
+        (app, Task::none())
+    }
+
+    fn view(&self) -> Element<'_, Self::Message> {
+        // Clicking the clock cycles the mode, just like the button below it
+        let clock = canvas::Canvas::new(
+            BinaryClock::new(self.current_time)
+                .mode(self.mode)
+                .show_labels(true)
+                .on_press(Message::NextMode),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill);
+
+        widget::column()
+            .push(clock)
+            .push(widget::button::standard("Next mode").on_press(Message::NextMode))
+            .padding(16)
+            .spacing(16)
+            .into()
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        cosmic::iced::time::every(std::time::Duration::from_secs(1)).map(|_| Message::Tick)
+    }
+
+    fn update(&mut self, message: Self::Message) -> Task<cosmic::Action<Self::Message>> {
+        match message {
+            Message::Tick => self.current_time = Local::now().fixed_offset(),
+            Message::NextMode => {
+                let index = DisplayMode::ALL.iter().position(|mode| *mode == self.mode).unwrap_or(0);
+                self.mode = DisplayMode::ALL[(index + 1) % DisplayMode::ALL.len()];
+            }
+        }
+        Task::none()
+    }
+}
+
+fn main() -> cosmic::iced::Result {
+    let settings = cosmic::app::Settings::default().size(Size::new(480.0, 320.0));
+    cosmic::app::run::<Embed>(settings, ())
+}
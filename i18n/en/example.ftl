@@ -4,4 +4,224 @@ view = View
 welcome = Welcome to COSMIC! ✨
 page-id = Page { $num }
 git-description = Git commit {$hash} on {$date}
-example-row = Example Row
+
+display-mode = Display mode
+mode-bcd = Binary coded decimal
+mode-binary = Binary
+twelve-hour = 12-hour time
+timezone = Time zone
+system-timezone = System time zone
+show-seconds = Show seconds
+active-color = Active color
+inactive-color = Inactive color
+theme-color = Theme color
+use-theme-colors = Use theme colors
+show-labels = Separate hours, minutes and seconds
+current-time = Time
+vertical-layout = Vertical layout
+heartbeat = Pulse every second
+mode-binary-seconds = Binary seconds
+mode-hex = Hexadecimal time
+cell-shape = Shape
+shape-circle = Circle
+shape-square = Square
+shape-rounded-square = Rounded square
+cell-spacing = Spacing
+inactive-style = Inactive bits
+inactive-filled = Filled
+inactive-outline = Outline
+inactive-hidden = Hidden
+current-date = Date
+show-date = Show the date
+
+# Used to write out the date in the popup. Reorder the parts to suit your language.
+date-format = { $weekday } { $day } { $month } { $year }
+weekday-mon = Mon
+weekday-tue = Tue
+weekday-wed = Wed
+weekday-thu = Thu
+weekday-fri = Fri
+weekday-sat = Sat
+weekday-sun = Sun
+month-jan = Jan
+month-feb = Feb
+month-mar = Mar
+month-apr = Apr
+month-may = May
+month-jun = Jun
+month-jul = Jul
+month-aug = Aug
+month-sep = Sep
+month-oct = Oct
+month-nov = Nov
+month-dec = Dec
+lsb-first = Least significant bit first
+
+# Read out in place of the clock itself, which is only a picture of circles. The time is
+# spelled out in words, the way it is said, so it isn't read as a list of digits
+accessible-time = The time is { $time }
+spoken-time = { $hour } { $minute }
+# A minute under ten, as in "three oh five"
+spoken-time-oh = { $hour } oh { $minute }
+spoken-time-twelve-hour-on-the-hour = { $hour } o'clock
+spoken-time-twenty-four-hour-on-the-hour = { $hour } hundred
+# The time in twelve hours followed by time-am or time-pm
+spoken-time-with-period = { $time } { $period }
+time-am = AM
+time-pm = PM
+# The words for the hours and minutes, from 0 to 59
+number-word = { $number ->
+    [0] zero
+    [1] one
+    [2] two
+    [3] three
+    [4] four
+    [5] five
+    [6] six
+    [7] seven
+    [8] eight
+    [9] nine
+    [10] ten
+    [11] eleven
+    [12] twelve
+    [13] thirteen
+    [14] fourteen
+    [15] fifteen
+    [16] sixteen
+    [17] seventeen
+    [18] eighteen
+    [19] nineteen
+    [20] twenty
+    [21] twenty-one
+    [22] twenty-two
+    [23] twenty-three
+    [24] twenty-four
+    [25] twenty-five
+    [26] twenty-six
+    [27] twenty-seven
+    [28] twenty-eight
+    [29] twenty-nine
+    [30] thirty
+    [31] thirty-one
+    [32] thirty-two
+    [33] thirty-three
+    [34] thirty-four
+    [35] thirty-five
+    [36] thirty-six
+    [37] thirty-seven
+    [38] thirty-eight
+    [39] thirty-nine
+    [40] forty
+    [41] forty-one
+    [42] forty-two
+    [43] forty-three
+    [44] forty-four
+    [45] forty-five
+    [46] forty-six
+    [47] forty-seven
+    [48] forty-eight
+    [49] forty-nine
+    [50] fifty
+    [51] fifty-one
+    [52] fifty-two
+    [53] fifty-three
+    [54] fifty-four
+    [55] fifty-five
+    [56] fifty-six
+    [57] fifty-seven
+    [58] fifty-eight
+   *[other] fifty-nine
+}
+# Shown when the hour is chimed
+current-time = The time is { $time }
+meridiem-dot = Show AM/PM as a corner dot
+mode-mixed = Binary hours, BCD minutes
+popup-width = Popup width
+popup-height = Popup height
+animations = Fade bits as they change
+place-value-tint = Tint rows by place value
+secondary-zone = Second time zone
+no-secondary-zone = None
+local-zone = Local
+brightness = Brightness
+night-mode = Dim at night
+night-start = Night starts at
+night-end = Night ends at
+blank-leading-zero = Hide the leading zero hour
+show-background = Show a background panel
+per-field-colors = Color hours, minutes and seconds apart
+hour-color = Hour color
+minute-color = Minute color
+second-color = Second color
+reset-settings = Reset to defaults
+minute-pulse = Flash at the start of every minute
+follow-system-hour-format = Use the system time format
+mode-compact-row = Single row
+show-digit-labels = Show the value below each column
+hourly-chime = Notify at the start of every hour
+mode-stopwatch = Stopwatch
+stopwatch-start = Start
+stopwatch-stop = Stop
+stopwatch-reset = Reset
+scale = Size
+high-contrast = High contrast
+show-weekday = Show the day of the week
+label-weekdays = Write the days in the circles
+day-of-month = Day of the month
+show-day-of-month = Show the day of the month in binary
+show-day-progress = Show how far through the day it is
+export-image = Save as image
+image-saved = Saved to { $path }
+image-not-saved = Couldn't save the image: { $error }
+show-deciseconds = Show tenths of a second
+mode-unix-epoch = Unix time
+epoch-bits = Bits shown
+import-settings = Import settings
+export-settings = Export settings
+mirror = Seconds first
+dim-when-idle = Dim while the session is idle
+cell-border = Outline lit bits
+invalid-timezone = Not a known time zone, such as Europe/Amsterdam
+padding = Padding
+side-padding = Side padding
+show-place-values = Write place values in the circles
+alarm = Alarm
+no-alarm = HH:MM
+invalid-alarm-time = Not a time, use HH:MM or HH:MM:SS
+alarm-ringing = Alarm: { $time }
+mode-countdown = Countdown
+countdown-pause = Pause
+countdown-minutes = Minutes
+countdown-finished = Time is up
+compact-panel = Only hours and minutes in the panel
+blink-separator = Blinking colon
+gradient = Gradient from low to high bits
+gradient-start = Lowest bit color
+gradient-end = Highest bit color
+alignment = Alignment
+align-left = Left
+align-center = Center
+align-right = Right
+fullscreen = Show fullscreen
+seconds-as-ring = Seconds as a ring
+week-starts-on = Week starts on
+week-start-locale = As usual in your region
+week-start-monday = Monday
+week-start-sunday = Sunday
+tutorial = How to read it
+tutorial-intro = Every column is a number. Each circle counts for the value written in it, so add up the lit ones to read the column.
+tutorial-column = Column { $column } of { $count }: { $sum } = { $value }
+tutorial-back = Back
+tutorial-next = Next
+tutorial-done = Done
+group-gap = Gap between hours, minutes and seconds
+preset = Color preset
+preset-matrix = Matrix
+preset-amber-crt = Amber CRT
+preset-ice-blue = Ice blue
+invert = Invert lit and unlit colors
+hex = Hex
+rgb = RGB
+recent-colors = Recent colors
+copy-to-clipboard = Copy to clipboard
+copied-to-clipboard = Copied to clipboard
@@ -1,93 +1,51 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use crate::config::Config;
 use crate::fl;
-use chrono::Timelike;
-use chrono::{DateTime, FixedOffset, Local, TimeZone};
-use cosmic::cosmic_config::{self, CosmicConfigEntry};
-use cosmic::iced::mouse;
+use chrono::{Datelike, Timelike, Weekday};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveTime, Utc};
+use chrono_tz::Tz;
+use cosmic::cosmic_config::{self, ConfigGet, CosmicConfigEntry};
+use cosmic::iced::keyboard::{self, key::Named, Key, Modifiers};
 use cosmic::iced::widget::canvas;
-use cosmic::iced::{window::Id, Limits, Subscription};
-use cosmic::iced::{Color, Rectangle, Renderer, Theme};
+use cosmic::iced::window::{self, Id, Screenshot};
+use cosmic::iced::{Length, Limits, Subscription};
+use cosmic::iced::{Alignment, Background, Border, Color};
 use cosmic::iced_winit::commands::popup::{destroy_popup, get_popup};
+use cosmic::dialog::file_chooser;
 use cosmic::prelude::*;
 use cosmic::widget;
+use cosmic::widget::color_picker::{ColorPickerModel, ColorPickerUpdate};
 use cosmic::widget::Canvas;
 use cosmic::Element;
+use example::clock::{BinaryClock, MAX_GROUP_GAP, MAX_PADDING, MAX_SCALE, MAX_SIDE_PADDING, MIN_SCALE};
+use example::config::{
+    format_hex_color, parse_alarm_time, parse_hex_color, BitOrder, CellShape, ClockAlignment, Config, DisplayMode, InactiveStyle, MeridiemStyle,
+    Orientation, Preset, SystemTimeConfig, WeekStart, COUNTDOWN_MINUTES_RANGE, EPOCH_BITS_RANGE, POPUP_HEIGHT_RANGE, POPUP_WIDTH_RANGE,
+};
 use futures_util::SinkExt;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
-const UTC_OFFSET_SECONDS: i32 = 3600;
-const ROWS: u8 = 4;
-
-#[derive(Debug)]
-enum DisplayMode {
-    BCD,
-    BINARY,
-}
-
-// First, we define the data we need for drawing
-#[derive(Debug)]
-struct ClockWidget {
-    mode: DisplayMode,
-    current_time: DateTime<FixedOffset>,
-}
-
-impl ClockWidget {
-    //@TODO: remove all the padding from this code
-    fn column(&self, index: u8, number: u32, renderer: &Renderer, bounds: Rectangle) -> canvas::Frame {
-        let mut frame = canvas::Frame::new(renderer, bounds.size());
-        //some padding until i learn how to properly size the widget..
-        let padding = 14.0;
-        // This is the amount of space we have available, subtract the hardcoded padding
-        let available_height = bounds.size().height - padding;
-        // The radius will be the the available height divided by the number of circles times two
-        let radius = available_height / (ROWS * 2) as f32;
-        // Start at the very top, in the center of the available frame but add the padding
-        let mut position = cosmic::iced::Point { x: (radius * 2.0) * index as f32, y: padding / 2.0};
-        position.x += radius;
-        // Increment said position by the radius, so that the first circle just touches the boundary rather than be on it
-        position.y += radius;
-        for circle_row in (0..ROWS as usize).rev() {
-            let circle = canvas::Path::circle(position, radius);
-            let active_color = Color::from_rgb(0.7, 0.7, 0.7);//Color::WHITE;
-            let inactive_color = Color::from_rgb(0.2, 0.2, 0.2);;
-            let circle_color = if number & (1 << circle_row) != 0 { active_color } else { inactive_color };
-            frame.fill(&circle, circle_color);
-            position.y += radius * 2.0;
-        }
-        frame
-    }
-}
-
-impl<Message, Theme> cosmic::widget::canvas::Program<Message, Theme> for ClockWidget {
-    type State = ();
-
-    fn draw(
-        &self,
-        _state: &(),
-        renderer: &Renderer,
-        _theme: &Theme,
-        bounds: Rectangle,
-        _cursor: mouse::Cursor,
-    ) -> Vec<canvas::Geometry> {
-
-        let hours_tens_place    = self.column(0, self.current_time.hour() / 10, renderer, bounds);
-        let hours               = self.column(1, self.current_time.hour() % 10, renderer, bounds);
-        let ten_minutes         = self.column(2, self.current_time.minute() / 10, renderer, bounds);
-        let minutes             = self.column(3, self.current_time.minute() % 10, renderer, bounds);
-        let tenth_seconds       = self.column(4, self.current_time.second() / 10, renderer, bounds);
-        let seconds             = self.column(5, self.current_time.second() % 10, renderer, bounds);
-
-        vec![
-            hours_tens_place.into_geometry(),
-            hours.into_geometry(),
-            ten_minutes.into_geometry(),
-            minutes.into_geometry(),
-            tenth_seconds.into_geometry(),
-            seconds.into_geometry()
-        ]
-    }
-}
+// How much the popup size settings change per step, in logical pixels
+const POPUP_SIZE_STEP: u32 = 20;
+// Height of the large clock at the top of the settings popup, in logical pixels
+const POPUP_CLOCK_HEIGHT: f32 = 120.0;
+// How much the epoch bits setting changes per step, so it goes 8, 16, 24, 32
+const EPOCH_BITS_STEP: u32 = 8;
+// How often animations advance while one is running, about 30 times a second
+const ANIMATION_INTERVAL_MS: u64 = 33;
+// How long the heartbeat takes to fade out
+const HEARTBEAT_MS: u64 = 500;
+// How long a bit takes to fade from one state to the other
+const FADE_MS: u64 = 300;
+// Where the COSMIC panel's own clock keeps its settings, including the desktop's time format
+const SYSTEM_TIME_CONFIG_ID: &str = "com.system76.CosmicAppletTime";
+// How long the flash at the start of a minute takes to fade out
+const MINUTE_PULSE_MS: u64 = 500;
+// How far the time may move on from one tick to the next beyond the tick interval before it counts as
+// a jump, like after a suspend or the clock being set
+const CLOCK_JUMP_MS: i64 = 2000;
 
 /// The application model stores app-specific state used to describe its interface and
 /// drive its logic.
@@ -95,24 +53,265 @@ impl<Message, Theme> cosmic::widget::canvas::Program<Message, Theme> for ClockWi
 pub struct AppModel {
     /// Application state which is managed by the COSMIC runtime.
     core: cosmic::Core,
+    /// Running as a normal window rather than in the panel.
+    windowed: bool,
+    /// Whether the settings are shown below the clock, in window mode.
+    show_settings: bool,
     /// The popup id.
     popup: Option<Id>,
+    /// The window showing the clock fullscreen, while it is open.
+    overlay: Option<Id>,
+    /// What the open popup shows.
+    popup_kind: PopupKind,
+    /// The column the tutorial is explaining.
+    tutorial_column: usize,
+    /// Handle used to write configuration changes back to disk.
+    config_handler: Option<cosmic_config::Config>,
     /// Configuration data that persists between application runs.
     config: Config,
-    /// Example row toggler.
-    example_row: bool,
+    /// Localized names of the display modes, in `DisplayMode::ALL` order.
+    mode_labels: Vec<String>,
+    /// Localized names of the cell shapes, in `CellShape::ALL` order.
+    shape_labels: Vec<String>,
+    /// Localized names of the alignments, in `ClockAlignment::ALL` order.
+    alignment_labels: Vec<String>,
+    /// Localized names of the inactive cell styles, in `InactiveStyle::ALL` order.
+    inactive_style_labels: Vec<String>,
+    /// Localized names of the week starts, in [`WeekStart::ALL`] order.
+    week_start_labels: Vec<String>,
+    /// Names of the color presets, in [`Preset::ALL`] order.
+    preset_labels: Vec<String>,
+    /// Text in the active color entry, which may not be a valid color yet.
+    active_color_input: String,
+    /// Text in the inactive color entry, which may not be a valid color yet.
+    inactive_color_input: String,
+    /// Text in the time zone entry, which may not be a known zone yet.
+    timezone_input: String,
+    /// Text in the second time zone entry, which may not be a known zone yet.
+    secondary_zone_input: String,
+    /// Text in the alarm time entry, which may not be a valid time yet.
+    alarm_input: String,
+    /// The day the alarm last went off, so it only goes off once a day.
+    alarm_fired: Option<NaiveDate>,
+    /// Text in the hour, minute and second color entries.
+    field_color_inputs: [String; 3],
+    /// Text in the gradient start and end color entries.
+    gradient_color_inputs: [String; 2],
+    /// The color picker opened from a swatch, and which color it changes.
+    color_picker: Option<(ColorTarget, ColorPickerModel)>,
+    /// Where the last picture of the clock was saved, or why it couldn't be.
+    image_export: Option<Result<PathBuf, String>>,
+    /// Brightness of the heartbeat, restarted on every tick while enabled.
+    heartbeat: f32,
+    /// The time shown before the last tick, while bits are fading from it.
+    previous_time: Option<DateTime<FixedOffset>>,
+    /// How far bits have faded from `previous_time`.
+    fade: f32,
+    /// Brightness of the flash over the whole clock, restarted at the start of every minute while enabled.
+    minute_pulse: f32,
+    /// Whether the desktop shows twelve hour times, as of the last time it was checked.
+    system_twelve_hour: bool,
+    /// When the running stopwatch was last started or reset, or `None` while it is stopped.
+    stopwatch_start: Option<Instant>,
+    /// Time on the stopwatch from before it was last started.
+    stopwatch_elapsed: Duration,
+    /// When the running countdown reaches zero, or `None` while it is paused or over.
+    countdown_end: Option<Instant>,
+    /// Time left on the countdown while it isn't running.
+    countdown_left: Duration,
+    /// Whether the clock has ticked since it started, so there is a previous tick to compare with.
+    ticked: bool,
+    /// Whether the screensaver is on, while dimming when idle is enabled.
+    idle: bool,
+    /// Whether the desktop asks for reduced motion, which turns every animation off.
+    reduced_motion: bool,
+    /// The name of the output the panel showing the applet is on, to pick its own settings by.
+    output_name: String,
     current_time: DateTime<FixedOffset>,
 }
 
+/// Startup options, read from the command line and the environment.
+#[derive(Debug, Default, Clone)]
+pub struct Flags {
+    /// Run as a normal resizable window with a large clock, instead of as a panel applet.
+    pub windowed: bool,
+    /// Switch to twelve (`true`) or twenty-four (`false`) hour mode at startup.
+    pub twelve_hour: Option<bool>,
+}
+
+impl Flags {
+    /// Reads `--window`, `--12h` and `--24h` from the command line. Without either of the latter,
+    /// `BINARY_CLOCK_HOURS=12` or `BINARY_CLOCK_HOURS=24` in the environment picks the hour mode.
+    pub fn from_args() -> Self {
+        let mut flags = Self {
+            twelve_hour: match std::env::var("BINARY_CLOCK_HOURS").as_deref() {
+                Ok("12") => Some(true),
+                Ok("24") => Some(false),
+                _ => None,
+            },
+            ..Self::default()
+        };
+        for arg in std::env::args().skip(1) {
+            match arg.as_str() {
+                "--window" => flags.windowed = true,
+                "--12h" => flags.twelve_hour = Some(true),
+                "--24h" => flags.twelve_hour = Some(false),
+                _ => {}
+            }
+        }
+        flags
+    }
+}
+
+/// The popups the applet can open.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum PopupKind {
+    /// Every setting, opened with a left click.
+    #[default]
+    Settings,
+    /// A short menu of the most used settings, opened with a right click.
+    ContextMenu,
+    /// A walk through reading the clock, one column at a time, opened from the settings.
+    Tutorial,
+}
+
+/// The colors that can be picked in the settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorTarget {
+    Active,
+    Inactive,
+    /// The hours, minutes or seconds, by index.
+    Field(usize),
+    /// The start or end of the gradient, by index.
+    Gradient(usize),
+}
+
+impl ColorTarget {
+    /// The message that sets this color to `color`.
+    fn set(self, color: Color) -> Message {
+        match self {
+            Self::Active => Message::SetActiveColor(color),
+            Self::Inactive => Message::SetInactiveColor(color),
+            Self::Field(field) => Message::SetFieldColor(field, color),
+            Self::Gradient(end) => Message::SetGradientColor(end, color),
+        }
+    }
+}
+
 /// Messages emitted by the application and its widgets.
 #[derive(Debug, Clone)]
 pub enum Message {
     TogglePopup,
+    OpenContextMenu,
     Tick,
+    AnimationFrame,
     PopupClosed(Id),
     SubscriptionChannel,
     UpdateConfig(Config),
-    ToggleExampleRow(bool),
+    /// The panel's clock switched between twelve and twenty-four hours.
+    SystemHourFormatChanged,
+    SetDisplayMode(DisplayMode),
+    CycleMode,
+    /// Switches to the display mode before the current one.
+    CycleModeBack,
+    /// Switches the popup to the tutorial, starting at the first column.
+    ShowTutorial,
+    /// Moves the tutorial on to the column at the given index.
+    TutorialColumn(usize),
+    /// Switches the popup from the tutorial back to the settings.
+    CloseTutorial,
+    /// Opens a window showing nothing but the clock, fullscreen.
+    EnterFullscreen,
+    ExitFullscreen,
+    /// Starts the stopwatch, or stops it while it is running.
+    ToggleStopwatch,
+    ResetStopwatch,
+    /// Starts the countdown, or pauses it while it is running.
+    ToggleCountdown,
+    ResetCountdown,
+    SetCountdownMinutes(u32),
+    ToggleTwelveHour(bool),
+    ToggleFollowSystemHourFormat(bool),
+    ToggleBlankLeadingZero(bool),
+    SetMeridiemStyle(MeridiemStyle),
+    /// Text typed into the time zone entry, only saved once it names a known zone.
+    TimezoneInputChanged(String),
+    SecondaryZoneInputChanged(String),
+    AlarmInputChanged(String),
+    SetPopupWidth(u32),
+    SetPopupHeight(u32),
+    SetEpochBits(u32),
+    ToggleShowSeconds(bool),
+    ToggleSecondsAsRing(bool),
+    ToggleShowDeciseconds(bool),
+    ToggleShowLabels(bool),
+    ToggleBlinkSeparator(bool),
+    ToggleMirror(bool),
+    ToggleShowDate(bool),
+    ToggleShowBackground(bool),
+    ToggleShowDigitLabels(bool),
+    ToggleShowWeekday(bool),
+    ToggleShowDayOfMonth(bool),
+    ToggleLabelWeekdays(bool),
+    SetWeekStart(WeekStart),
+    ToggleShowDayProgress(bool),
+    ToggleCompactPanel(bool),
+    SetOrientation(Orientation),
+    SetAlignment(ClockAlignment),
+    SetCellShape(CellShape),
+    SetCellSpacing(f32),
+    SetGroupGap(f32),
+    SetScale(f32),
+    SetPadding(f32),
+    SetSidePadding(f32),
+    SetBrightness(f32),
+    ToggleNightMode(bool),
+    ToggleDimWhenIdle(bool),
+    IdleStateChanged(bool),
+    ReducedMotionChanged(bool),
+    SetNightStart(u32),
+    SetNightEnd(u32),
+    SetInactiveStyle(InactiveStyle),
+    SetBitOrder(BitOrder),
+    ToggleHeartbeat(bool),
+    ToggleAnimations(bool),
+    ToggleMinutePulse(bool),
+    ToggleHourlyChime(bool),
+    TogglePlaceValueTint(bool),
+    ToggleShowPlaceValues(bool),
+    ToggleHighContrast(bool),
+    ToggleInvert(bool),
+    ToggleCellBorder(bool),
+    SetActiveColor(Color),
+    SetInactiveColor(Color),
+    ActiveColorInput(String),
+    InactiveColorInput(String),
+    TogglePerFieldColors(bool),
+    /// Sets the color of the hours (0), minutes (1) or seconds (2).
+    SetFieldColor(usize, Color),
+    FieldColorInput(usize, String),
+    ToggleGradient(bool),
+    /// Sets the gradient's start (0) or end (1) color.
+    SetGradientColor(usize, Color),
+    GradientColorInput(usize, String),
+    ApplyPreset(Preset),
+    /// Opens the color picker for a color, starting from the one in use, or closes it if it is open.
+    OpenColorPicker(ColorTarget, Color),
+    ColorPicker(ColorPickerUpdate),
+    UseThemeColors,
+    ResetConfig,
+    /// Ask where to save a picture of the clock, then save it there.
+    ChooseImagePath,
+    /// Save a picture of the clock as it is shown to a PNG file.
+    ExportImage(PathBuf),
+    ImageCaptured(PathBuf, Screenshot),
+    ImageExported(Result<PathBuf, String>),
+    /// Ask where to save the settings, then save them there.
+    ChooseExportPath,
+    /// Ask which settings file to load, then load it.
+    ChooseImportPath,
+    ExportConfig(PathBuf),
+    ImportConfig(PathBuf),
 }
 
 /// Create a COSMIC application from the app model
@@ -121,7 +320,7 @@ impl cosmic::Application for AppModel {
     type Executor = cosmic::executor::Default;
 
     /// Data that your application receives to its init method.
-    type Flags = ();
+    type Flags = Flags;
 
     /// Messages which the application and its widgets will emit.
     type Message = Message;
@@ -140,34 +339,68 @@ impl cosmic::Application for AppModel {
     /// Initializes the application with any given flags and startup commands.
     fn init(
         core: cosmic::Core,
-        _flags: Self::Flags,
+        flags: Self::Flags,
     ) -> (Self, Task<cosmic::Action<Self::Message>>) {
 
-        let offset = FixedOffset::east_opt(UTC_OFFSET_SECONDS).unwrap();
-        let current_time = Local::now().with_timezone(&offset);
+        let config_handler = cosmic_config::Config::new(Self::APP_ID, Config::VERSION).ok();
+        let mut config = config_handler
+            .as_ref()
+            .map(|context| {
+                let previous = cosmic_config::Config::new(Self::APP_ID, Config::VERSION - 1).ok();
+                Config::load(context, previous.as_ref())
+            })
+            .unwrap_or_default();
+        config.discard_invalid_colors();
+        let twelve_hour_changed = flags.twelve_hour.is_some_and(|twelve_hour| {
+            twelve_hour != config.twelve_hour || config.follow_system_hour_format
+        });
+        if let Some(twelve_hour) = flags.twelve_hour {
+            config.twelve_hour = twelve_hour;
+            config.follow_system_hour_format = false;
+        }
+        let output_name = core.applet.output_name.clone();
         // Construct the app model with the runtime's core.
-        let app = AppModel {
-            current_time,
+        let mut app = AppModel {
+            output_name,
             core,
-            config: cosmic_config::Config::new(Self::APP_ID, Config::VERSION)
-                .map(|context| match Config::get_entry(&context) {
-                    Ok(config) => config,
-                    Err((_errors, config)) => {
-                        // for why in errors {
-                        //     tracing::error!(%why, "error loading app config");
-                        // }
-
-                        config
-                    }
-                })
-                .unwrap_or_default(),
+            windowed: flags.windowed,
+            config_handler,
+            mode_labels: DisplayMode::ALL.iter().map(|mode| mode_label(*mode)).collect(),
+            shape_labels: CellShape::ALL.iter().map(|shape| shape_label(*shape)).collect(),
+            alignment_labels: ClockAlignment::ALL.iter().map(|alignment| alignment_label(*alignment)).collect(),
+            inactive_style_labels: InactiveStyle::ALL.iter().map(|style| inactive_style_label(*style)).collect(),
+            week_start_labels: WeekStart::ALL.iter().map(|week_start| week_start_label(*week_start)).collect(),
+            preset_labels: Preset::ALL.iter().map(|preset| preset_label(*preset)).collect(),
+            active_color_input: config.active_color.clone().unwrap_or_default(),
+            inactive_color_input: config.inactive_color.clone().unwrap_or_default(),
+            field_color_inputs: config.field_colors.clone().map(Option::unwrap_or_default),
+            gradient_color_inputs: gradient_inputs(&config),
+            timezone_input: config.timezone.clone(),
+            secondary_zone_input: config.secondary_zone.clone().unwrap_or_default(),
+            alarm_input: config.alarm_time.clone().unwrap_or_default(),
+            system_twelve_hour: system_twelve_hour(),
+            // A stopwatch left showing from the last run starts counting again from zero
+            stopwatch_start: (config.mode == DisplayMode::Stopwatch).then(Instant::now),
+            countdown_left: config.countdown(),
+            config,
             ..Default::default()
         };
+        app.refresh_time();
+        // Saved like a change made in the settings, so the config watcher doesn't switch it back
+        if twelve_hour_changed {
+            app.save_config();
+        }
 
-        (app, Task::none())
+        // Tick straight away rather than waiting for the first boundary
+        (app, Task::done(cosmic::Action::App(Message::Tick)))
     }
 
     fn on_close_requested(&self, id: Id) -> Option<Message> {
+        // Closing the window in window mode quits, there is no popup to clean up. The fullscreen
+        // clock can still be closed by itself, and has to be forgotten so it can be opened again
+        if self.windowed && self.overlay != Some(id) {
+            return None;
+        }
         Some(Message::PopupClosed(id))
     }
 
@@ -177,41 +410,76 @@ impl cosmic::Application for AppModel {
     /// This view should emit messages to toggle the applet's popup window, which will
     /// be drawn using the `view_window` method.
     fn view(&self) -> Element<'_, Self::Message> {
-        let c: Canvas<ClockWidget, Message, cosmic::Theme, cosmic::Renderer> =
-            canvas::Canvas::new(ClockWidget {
-                current_time: self.current_time,
-                mode: DisplayMode::BCD,
-            });
-
-        cosmic::widget::Container::new(c)
-            //.width(400)
-            //.max_width(400)
-            //.padding(5)
-            .into()
-        //cosmic::widget::text("abcdefgthi").into()
+        if self.windowed {
+            return self.window_view();
+        }
 
-        /*
-        cosmic::widget::list_column()
-            .padding(0)       // <-- adjust padding here
-            .spacing(0)
-            .add(c)
+        // The popup always has the full clock, so the panel can make do with less.
+        // Scrolling over it flips through the modes without opening the popup
+        let panel_clock = || {
+            self.clock_widget()
+                .compact(self.config.compact_panel)
+                .on_scroll(Message::CycleMode, Message::CycleModeBack)
+        };
+        let c = self.panel_canvas(panel_clock());
+
+        // With a second zone set, each zone gets a clock of its own, named by a small label in front of it
+        let clocks: Element<'_, Message> = match self.config.secondary_zone() {
+            Some(zone) => {
+                let secondary_time = self.current_time.with_timezone(&zone);
+                let secondary = self.panel_canvas(panel_clock().timezone(*secondary_time.fixed_offset().offset()));
+                widget::row()
+                    .push(widget::text::caption(fl!("local-zone")))
+                    .push(c)
+                    .push(widget::text::caption(secondary_time.format("%Z").to_string()))
+                    .push(secondary)
+                    .spacing(4)
+                    .align_y(Alignment::Center)
+                    .into()
+            }
+            None => c.into(),
+        };
+
+        // A canvas is just pixels with no name of its own, and only buttons can be named, so the
+        // clock sits in the applet's button with the time in words as its name. Presses still reach
+        // the clock first, the button's own is for screen readers activating it
+        widget::button::custom(clocks)
+            .padding(0)
+            .class(cosmic::theme::Button::AppletIcon)
+            .name(self.accessible_label())
+            .on_press(Message::TogglePopup)
             .into()
-            */
     }
 
     /// The applet's popup window will be drawn using this view method. If there are
     /// multiple poups, you may match the id parameter to determine which popup to
     /// create a view for.
-    fn view_window(&self, _id: Id) -> Element<'_, Self::Message> {
-        let content_list = widget::list_column()
-            //.padding(5)
-            .spacing(0)
-            .add(widget::settings::item(
-                fl!("example-row"),
-                widget::toggler(self.example_row).on_toggle(Message::ToggleExampleRow),
-            ));
+    fn view_window(&self, id: Id) -> Element<'_, Self::Message> {
+        if self.overlay == Some(id) {
+            return self.overlay_view();
+        }
+
+        match self.popup_kind {
+            PopupKind::ContextMenu => return self.context_menu(),
+            PopupKind::Tutorial => return self.tutorial_view(),
+            PopupKind::Settings => {}
+        }
 
-        self.core.applet.popup_container(content_list).into()
+        // A larger copy of the clock in the panel, which is easier to read. Clicking it
+        // switches modes, so they can be compared at a size where the difference shows.
+        let clock: Canvas<BinaryClock<Message>, Message, cosmic::Theme, cosmic::Renderer> =
+            canvas::Canvas::new(self.clock_widget().column_hints(true).on_press(Message::CycleMode))
+                .width(Length::Fill)
+                .height(Length::Fixed(POPUP_CLOCK_HEIGHT));
+        let buttons = widget::row()
+            .push(widget::button::standard(fl!("fullscreen")).on_press(Message::EnterFullscreen))
+            .push(widget::button::standard(fl!("tutorial")).on_press(Message::ShowTutorial))
+            .spacing(8);
+        let content = widget::column()
+            .push(widget::container(clock).padding([8, 16]))
+            .push(widget::container(buttons).padding([0, 16]))
+            .push(self.settings_view());
+        self.core.applet.popup_container(content).into()
     }
 
     /// Register subscriptions for this application.
@@ -223,7 +491,7 @@ impl cosmic::Application for AppModel {
     fn subscription(&self) -> Subscription<Self::Message> {
         struct MySubscription;
 
-        Subscription::batch(vec![
+        let mut subscriptions = vec![
             // Create a subscription which emits updates through a channel.
             Subscription::run_with_id(
                 std::any::TypeId::of::<MySubscription>(),
@@ -244,8 +512,41 @@ impl cosmic::Application for AppModel {
 
                     Message::UpdateConfig(update.config)
                 }),
-            cosmic::iced::time::every(tokio::time::Duration::new(1,0)).map(|_|Message::Tick),
-        ])
+        ];
+
+        // The panel's clock has a config of its own, so changes to it need watching separately
+        if self.config.follow_system_hour_format {
+            subscriptions.push(
+                self.core()
+                    .watch_config::<SystemTimeConfig>(SYSTEM_TIME_CONFIG_ID)
+                    .map(|_| Message::SystemHourFormatChanged),
+            );
+        }
+
+        // Keyboard events only reach the surface that has keyboard focus, and keys typed into
+        // a text entry in the settings are captured by it, so shortcuts never fire from elsewhere
+        subscriptions.push(keyboard::on_key_press(shortcut));
+
+        // Without seconds on display the clock only changes once a minute, so don't wake up any more often,
+        // and only tick ten times a second while tenths are shown. Nobody watches an idle session's clock.
+        // A new interval from the config makes for a new subscription, which replaces the old one.
+        subscriptions.push(aligned_ticks(self.tick_period_ms()));
+
+        if self.config.dim_when_idle {
+            subscriptions.push(crate::idle::subscription().map(Message::IdleStateChanged));
+        }
+
+        subscriptions.push(crate::motion::subscription().map(Message::ReducedMotionChanged));
+
+        // Only wake up for animation frames when there is something to animate
+        if self.heartbeat > 0.0 || self.minute_pulse > 0.0 || self.previous_time.is_some() {
+            subscriptions.push(
+                cosmic::iced::time::every(tokio::time::Duration::from_millis(ANIMATION_INTERVAL_MS))
+                    .map(|_| Message::AnimationFrame),
+            );
+        }
+
+        Subscription::batch(subscriptions)
     }
 
     /// Handles messages emitted by the application and its widgets.
@@ -256,40 +557,601 @@ impl cosmic::Application for AppModel {
     fn update(&mut self, message: Self::Message) -> Task<cosmic::Action<Self::Message>> {
         match message {
             Message::Tick => {
-                let offset = FixedOffset::east_opt(UTC_OFFSET_SECONDS).unwrap();
-                self.current_time = Local::now().with_timezone(&offset);
+                let previous_time = self.current_time;
+                self.refresh_time();
+                // With tenths of a second shown, most ticks fall within the same second
+                let new_second = self.current_time.timestamp() != previous_time.timestamp();
+                #[cfg(feature = "status-file")]
+                if new_second {
+                    crate::status::publish(self.current_time, self.config.mode);
+                }
+                // Nothing moves while the desktop asks for reduced motion, whatever is enabled
+                let motion = !self.reduced_motion;
+                if self.config.heartbeat && motion && new_second {
+                    self.heartbeat = 1.0;
+                }
+                // Across a jump nearly every bit changes at once, and fading them all would sweep over
+                // the whole clock, so the new time is just shown
+                let moved_ms = (self.current_time - previous_time).num_milliseconds();
+                let jumped = is_clock_jump(moved_ms, self.tick_period_ms());
+                // Starts the fade, and the sweep down any column that rolled over, from the previous tick.
+                // Tenths of a second come and go faster than either would
+                if jumped {
+                    self.previous_time = None;
+                } else if self.config.animations && motion && new_second {
+                    self.previous_time = Some(previous_time);
+                    self.fade = 0.0;
+                }
+                // The first tick only catches up with the time the clock started at
+                let new_minute = self.current_time.timestamp() / 60 != previous_time.timestamp() / 60;
+                if self.config.minute_pulse && motion && self.ticked && new_minute {
+                    self.minute_pulse = 1.0;
+                }
+                // Compared in the shown zone, whose hours needn't start on the hour in UTC
+                let new_hour = (self.current_time.date_naive(), self.current_time.hour())
+                    != (previous_time.date_naive(), previous_time.hour());
+                let chime = self.config.hourly_chime && self.ticked && new_hour;
+                self.ticked = true;
+                let mut notifications = Vec::new();
+                if chime {
+                    notifications.push(notify(fl!("current-time", time = self.time_label())));
+                }
+                if self.countdown_end.is_some_and(|end| end <= Instant::now()) {
+                    self.countdown_end = None;
+                    self.countdown_left = Duration::ZERO;
+                    notifications.push(notify(fl!("countdown-finished")));
+                }
+                // A finished countdown keeps flashing every second until it is reset
+                if self.config.mode == DisplayMode::Countdown && self.countdown_finished() && motion && new_second {
+                    self.minute_pulse = 1.0;
+                }
+                if self.alarm_due(previous_time) {
+                    if motion {
+                        self.minute_pulse = 1.0;
+                    }
+                    notifications.push(notify(fl!("alarm-ringing", time = self.time_label())));
+                }
+                return Task::batch(notifications);
+            }
+            Message::AnimationFrame => {
+                let elapsed = ANIMATION_INTERVAL_MS as f32;
+                self.heartbeat = (self.heartbeat - elapsed / HEARTBEAT_MS as f32).max(0.0);
+                self.minute_pulse = (self.minute_pulse - elapsed / MINUTE_PULSE_MS as f32).max(0.0);
+                if self.previous_time.is_some() {
+                    self.fade += elapsed / FADE_MS as f32;
+                    if self.fade >= 1.0 {
+                        self.previous_time = None;
+                    }
+                }
             }
             Message::SubscriptionChannel => {
                 // For example purposes only.
             }
-            Message::UpdateConfig(config) => {
+            Message::UpdateConfig(mut config) => {
+                config.discard_invalid_colors();
                 self.config = config;
+                self.system_twelve_hour = system_twelve_hour();
+                self.refresh_time();
+            }
+            Message::SystemHourFormatChanged => self.system_twelve_hour = system_twelve_hour(),
+            Message::SetDisplayMode(mode) => self.set_mode(mode),
+            Message::CycleMode => {
+                let index = DisplayMode::ALL.iter().position(|mode| *mode == self.config.mode).unwrap_or(0);
+                self.set_mode(DisplayMode::ALL[(index + 1) % DisplayMode::ALL.len()]);
+            }
+            Message::CycleModeBack => {
+                let index = DisplayMode::ALL.iter().position(|mode| *mode == self.config.mode).unwrap_or(0);
+                self.set_mode(DisplayMode::ALL[(index + DisplayMode::ALL.len() - 1) % DisplayMode::ALL.len()]);
+            }
+            Message::ToggleStopwatch => match self.stopwatch_start.take() {
+                Some(start) => self.stopwatch_elapsed += start.elapsed(),
+                None => self.stopwatch_start = Some(Instant::now()),
+            },
+            Message::ResetStopwatch => {
+                self.stopwatch_elapsed = Duration::ZERO;
+                if self.stopwatch_start.is_some() {
+                    self.stopwatch_start = Some(Instant::now());
+                }
+            }
+            Message::ToggleCountdown => match self.countdown_end.take() {
+                Some(end) => self.countdown_left = end.saturating_duration_since(Instant::now()),
+                None => {
+                    // Starting a finished countdown runs it again from the top
+                    if self.countdown_finished() {
+                        self.countdown_left = self.config.countdown();
+                    }
+                    self.countdown_end = Some(Instant::now() + self.countdown_left);
+                }
+            },
+            Message::ResetCountdown => {
+                self.countdown_end = None;
+                self.countdown_left = self.config.countdown();
+            }
+            Message::SetCountdownMinutes(minutes) => {
+                self.config.countdown_minutes = minutes;
+                // A countdown that is underway keeps going, the new length applies from the next reset
+                if self.countdown_end.is_none() {
+                    self.countdown_left = self.config.countdown();
+                }
+                self.save_config();
+            }
+            Message::ToggleTwelveHour(enabled) => {
+                self.config.twelve_hour = enabled;
+                self.save_config();
+            }
+            Message::ToggleFollowSystemHourFormat(enabled) => {
+                self.config.follow_system_hour_format = enabled;
+                self.system_twelve_hour = system_twelve_hour();
+                self.save_config();
+            }
+            Message::ToggleBlankLeadingZero(enabled) => {
+                self.config.blank_leading_zero = enabled;
+                self.save_config();
+            }
+            Message::SetMeridiemStyle(style) => {
+                self.config.meridiem_style = style;
+                self.save_config();
+            }
+            Message::ToggleShowSeconds(enabled) => {
+                self.config.show_seconds = enabled;
+                self.save_config();
+            }
+            Message::ToggleSecondsAsRing(enabled) => {
+                self.config.seconds_as_ring = enabled;
+                self.save_config();
+            }
+            Message::ToggleShowDeciseconds(enabled) => {
+                self.config.show_deciseconds = enabled;
+                self.save_config();
+            }
+            Message::ToggleShowLabels(enabled) => {
+                self.config.show_labels = enabled;
+                self.save_config();
+            }
+            Message::ToggleBlinkSeparator(enabled) => {
+                self.config.blink_separator = enabled;
+                self.save_config();
+            }
+            Message::ToggleMirror(enabled) => {
+                self.config.mirror = enabled;
+                self.previous_time = None;
+                self.save_config();
+            }
+            Message::ToggleShowDate(enabled) => {
+                self.config.show_date = enabled;
+                self.save_config();
+            }
+            Message::ToggleShowBackground(enabled) => {
+                self.config.show_background = enabled;
+                self.save_config();
+            }
+            Message::ToggleShowDigitLabels(enabled) => {
+                self.config.show_digit_labels = enabled;
+                self.save_config();
+            }
+            Message::ToggleShowDayOfMonth(enabled) => {
+                self.config.show_day_of_month = enabled;
+                self.save_config();
+            }
+            Message::ToggleShowWeekday(enabled) => {
+                self.config.show_weekday = enabled;
+                self.save_config();
+            }
+            Message::ToggleLabelWeekdays(enabled) => {
+                self.config.label_weekdays = enabled;
+                self.save_config();
+            }
+            Message::SetWeekStart(week_start) => {
+                self.config.week_starts_on = week_start;
+                self.save_config();
+            }
+            Message::ToggleCompactPanel(enabled) => {
+                self.config.compact_panel = enabled;
+                self.save_config();
+            }
+            Message::ToggleShowDayProgress(enabled) => {
+                self.config.show_day_progress = enabled;
+                self.save_config();
+            }
+            Message::SetOrientation(orientation) => {
+                self.config.orientation = orientation;
+                self.save_config();
+            }
+            Message::SetAlignment(alignment) => {
+                self.config.alignment = alignment;
+                self.save_config();
+            }
+            Message::SetCellShape(shape) => {
+                self.config.cell_shape = shape;
+                self.save_config();
+            }
+            Message::SetCellSpacing(spacing) => {
+                self.config.cell_spacing = spacing;
+                self.save_config();
+            }
+            Message::SetGroupGap(gap) => {
+                self.config.group_gap = gap;
+                self.save_config();
+            }
+            Message::SetScale(scale) => {
+                self.config.scale = scale;
+                self.save_config();
+            }
+            Message::SetPadding(padding) => {
+                self.config.padding = padding;
+                self.save_config();
+            }
+            Message::SetSidePadding(padding) => {
+                self.config.side_padding = padding;
+                self.save_config();
+            }
+            Message::SetBrightness(brightness) => {
+                self.config.brightness = brightness;
+                self.save_config();
             }
-            Message::ToggleExampleRow(toggled) => self.example_row = toggled,
-            Message::TogglePopup => {
-                return if let Some(p) = self.popup.take() {
-                    destroy_popup(p)
+            Message::ToggleNightMode(enabled) => {
+                self.config.night_mode = enabled;
+                self.save_config();
+            }
+            Message::ToggleDimWhenIdle(enabled) => {
+                self.config.dim_when_idle = enabled;
+                self.idle = false;
+                self.save_config();
+            }
+            Message::IdleStateChanged(idle) => {
+                self.idle = idle && self.config.dim_when_idle;
+                // Catch up straight away rather than at the next minute
+                if !self.idle {
+                    self.refresh_time();
+                }
+            }
+            Message::ReducedMotionChanged(reduced) => {
+                self.reduced_motion = reduced;
+                // Stop whatever is already underway too
+                if reduced {
+                    self.heartbeat = 0.0;
+                    self.minute_pulse = 0.0;
+                    self.previous_time = None;
+                }
+            }
+            Message::SetNightStart(hour) => {
+                self.config.night_start = hour;
+                self.save_config();
+            }
+            Message::SetNightEnd(hour) => {
+                self.config.night_end = hour;
+                self.save_config();
+            }
+            Message::SetInactiveStyle(style) => {
+                self.config.inactive_style = style;
+                self.save_config();
+            }
+            Message::SetBitOrder(order) => {
+                self.config.bit_order = order;
+                self.save_config();
+            }
+            Message::ToggleHeartbeat(enabled) => {
+                self.config.heartbeat = enabled;
+                self.heartbeat = 0.0;
+                self.save_config();
+            }
+            Message::TogglePlaceValueTint(enabled) => {
+                self.config.place_value_tint = enabled;
+                self.save_config();
+            }
+            Message::ToggleShowPlaceValues(enabled) => {
+                self.config.show_place_values = enabled;
+                self.save_config();
+            }
+            Message::ToggleHighContrast(enabled) => {
+                self.config.high_contrast = enabled;
+                self.save_config();
+            }
+            Message::ToggleInvert(enabled) => {
+                self.config.invert = enabled;
+                self.save_config();
+            }
+            Message::ToggleCellBorder(enabled) => {
+                self.config.cell_border = enabled;
+                self.save_config();
+            }
+            Message::ToggleMinutePulse(enabled) => {
+                self.config.minute_pulse = enabled;
+                self.minute_pulse = 0.0;
+                self.save_config();
+            }
+            Message::ToggleHourlyChime(enabled) => {
+                self.config.hourly_chime = enabled;
+                self.save_config();
+            }
+            Message::ToggleAnimations(enabled) => {
+                self.config.animations = enabled;
+                self.previous_time = None;
+                self.save_config();
+            }
+            Message::SetActiveColor(color) => {
+                self.active_color_input = format_hex_color(color);
+                self.config.active_color = Some(self.active_color_input.clone());
+                self.save_config();
+            }
+            Message::SetInactiveColor(color) => {
+                self.inactive_color_input = format_hex_color(color);
+                self.config.inactive_color = Some(self.inactive_color_input.clone());
+                self.save_config();
+            }
+            Message::ActiveColorInput(text) => self.active_color_input = text,
+            Message::InactiveColorInput(text) => self.inactive_color_input = text,
+            Message::TogglePerFieldColors(enabled) => {
+                self.config.per_field_colors = enabled;
+                self.save_config();
+            }
+            Message::SetFieldColor(field, color) => {
+                self.field_color_inputs[field] = format_hex_color(color);
+                self.config.field_colors[field] = Some(self.field_color_inputs[field].clone());
+                self.save_config();
+            }
+            Message::FieldColorInput(field, text) => self.field_color_inputs[field] = text,
+            Message::ToggleGradient(enabled) => {
+                self.config.gradient = enabled;
+                self.save_config();
+            }
+            Message::SetGradientColor(end, color) => {
+                self.gradient_color_inputs[end] = format_hex_color(color);
+                let color = Some(self.gradient_color_inputs[end].clone());
+                if end == 0 {
+                    self.config.gradient_start = color;
+                } else {
+                    self.config.gradient_end = color;
+                }
+                self.save_config();
+            }
+            Message::GradientColorInput(end, text) => self.gradient_color_inputs[end] = text,
+            Message::OpenColorPicker(target, color) => {
+                if self.color_picker.as_ref().is_some_and(|(open, _)| *open == target) {
+                    self.color_picker = None;
                 } else {
-                    let new_id = Id::unique();
-                    self.popup.replace(new_id);
-                    let mut popup_settings = self.core.applet.get_popup_settings(
-                        self.core.main_window_id().unwrap(),
-                        new_id,
-                        None,
-                        None,
-                        None,
-                    );
-                    popup_settings.positioner.size_limits = Limits::NONE
-                        .max_width(372.0)
-                        .min_width(300.0)
-                        .min_height(200.0)
-                        .max_height(1080.0);
-                    get_popup(popup_settings)
+                    let mut picker = ColorPickerModel::new(fl!("hex"), fl!("rgb"), None, Some(color));
+                    // A new picker starts out folded away, and it was opened to be used
+                    let _ = picker.update::<cosmic::Action<Message>>(ColorPickerUpdate::ToggleColorPicker);
+                    self.color_picker = Some((target, picker));
+                }
+            }
+            Message::ColorPicker(update) => {
+                let Some((target, picker)) = self.color_picker.as_mut() else {
+                    return Task::none();
+                };
+                let target = *target;
+                let done = matches!(update, ColorPickerUpdate::AppliedColor | ColorPickerUpdate::Cancel);
+                let applied = matches!(update, ColorPickerUpdate::AppliedColor);
+                let task = picker.update::<cosmic::Action<Message>>(update);
+                let color = picker.get_applied_color().filter(|_| applied);
+                if done {
+                    self.color_picker = None;
+                }
+                return match color {
+                    Some(color) => task.chain(self.update(target.set(color))),
+                    None => task,
+                };
+            }
+            Message::ApplyPreset(preset) => {
+                let [active, inactive] = preset.colors();
+                self.active_color_input = active.to_string();
+                self.inactive_color_input = inactive.to_string();
+                // Colors picked for the old palette would clash, let them follow the new one
+                self.field_color_inputs = Default::default();
+                self.gradient_color_inputs = Default::default();
+                self.config.active_color = Some(self.active_color_input.clone());
+                self.config.inactive_color = Some(self.inactive_color_input.clone());
+                self.config.field_colors = Default::default();
+                self.config.gradient_start = None;
+                self.config.gradient_end = None;
+                self.save_config();
+            }
+            Message::UseThemeColors => {
+                self.active_color_input.clear();
+                self.inactive_color_input.clear();
+                self.field_color_inputs = Default::default();
+                self.gradient_color_inputs = Default::default();
+                self.config.active_color = None;
+                self.config.inactive_color = None;
+                self.config.field_colors = Default::default();
+                self.config.gradient_start = None;
+                self.config.gradient_end = None;
+                self.save_config();
+            }
+            Message::ResetConfig => {
+                self.config = Config::default();
+                self.active_color_input.clear();
+                self.inactive_color_input.clear();
+                self.timezone_input.clear();
+                self.secondary_zone_input.clear();
+                self.alarm_input.clear();
+                self.alarm_fired = None;
+                self.field_color_inputs = Default::default();
+                self.gradient_color_inputs = Default::default();
+                // The countdown starts over at the default length, rather than the old one
+                self.countdown_end = None;
+                self.countdown_left = self.config.countdown();
+                // Nothing left over from the old settings should keep animating
+                self.heartbeat = 0.0;
+                self.minute_pulse = 0.0;
+                self.previous_time = None;
+                self.refresh_time();
+                self.save_config();
+            }
+            Message::ChooseImagePath => {
+                let file_name = crate::export::file_name(self.current_time);
+                return Task::future(async move {
+                    let dialog = file_chooser::save::Dialog::new().title(fl!("export-image")).file_name(file_name);
+                    // A cancelled dialog is an error too, and there is nothing to do either way
+                    let path = dialog.save_file().await.ok().and_then(|response| response.url()?.to_file_path().ok());
+                    path.map_or(cosmic::Action::None, |path| cosmic::Action::App(Message::ExportImage(path)))
+                });
+            }
+            Message::ExportImage(path) => {
+                // The main window is the clock in the panel, or the clock window with what it shows below
+                if let Some(id) = self.core.main_window_id() {
+                    return window::screenshot(id)
+                        .map(move |screenshot| cosmic::Action::App(Message::ImageCaptured(path.clone(), screenshot)));
+                }
+            }
+            Message::ImageCaptured(path, screenshot) => {
+                return Task::future(async move {
+                    // Encoding is slow enough to stall the clock, so it happens off the event loop
+                    let written = tokio::task::spawn_blocking(move || {
+                        crate::export::write_png(&path, &screenshot)
+                            .map(|()| path.clone())
+                            .map_err(|why| format!("{}: {why}", path.display()))
+                    })
+                    .await
+                    .unwrap_or_else(|why| Err(why.to_string()));
+                    cosmic::Action::App(Message::ImageExported(written))
+                });
+            }
+            Message::ImageExported(written) => {
+                if let Err(why) = &written {
+                    eprintln!("error exporting image {why}");
+                }
+                self.image_export = Some(written);
+            }
+            Message::ChooseExportPath => {
+                return Task::future(async {
+                    let dialog = file_chooser::save::Dialog::new()
+                        .title(fl!("export-settings"))
+                        .file_name("binary-clock.toml");
+                    // A cancelled dialog is an error too, and there is nothing to do either way
+                    let path = dialog.save_file().await.ok().and_then(|response| response.url()?.to_file_path().ok());
+                    path.map_or(cosmic::Action::None, |path| cosmic::Action::App(Message::ExportConfig(path)))
+                });
+            }
+            Message::ChooseImportPath => {
+                return Task::future(async {
+                    let dialog = file_chooser::open::Dialog::new().title(fl!("import-settings"));
+                    let path = dialog.open_file().await.ok().and_then(|response| response.url().to_file_path().ok());
+                    path.map_or(cosmic::Action::None, |path| cosmic::Action::App(Message::ImportConfig(path)))
+                });
+            }
+            Message::ExportConfig(path) => {
+                let written = self
+                    .config
+                    .to_toml()
+                    .map_err(|why| why.to_string())
+                    .and_then(|text| std::fs::write(&path, text).map_err(|why| why.to_string()));
+                if let Err(why) = written {
+                    eprintln!("error exporting settings to {}: {why}", path.display());
+                }
+            }
+            Message::ImportConfig(path) => {
+                let imported = std::fs::read_to_string(&path)
+                    .map_err(|why| why.to_string())
+                    .and_then(|text| Config::from_toml(&text).map_err(|why| why.to_string()));
+                match imported {
+                    Ok((mut config, ignored)) => {
+                        for key in ignored {
+                            eprintln!("ignoring invalid setting {key} in {}", path.display());
+                        }
+                        config.discard_invalid_colors();
+                        self.active_color_input = config.active_color.clone().unwrap_or_default();
+                        self.inactive_color_input = config.inactive_color.clone().unwrap_or_default();
+                        self.field_color_inputs = config.field_colors.clone().map(Option::unwrap_or_default);
+                        self.gradient_color_inputs = gradient_inputs(&config);
+                        self.timezone_input = config.timezone.clone();
+                        self.secondary_zone_input = config.secondary_zone.clone().unwrap_or_default();
+                        self.alarm_input = config.alarm_time.clone().unwrap_or_default();
+                        // An imported alarm may be due today even if the old one went off already
+                        self.alarm_fired = None;
+                        self.config = config;
+                        self.countdown_end = None;
+                        self.countdown_left = self.config.countdown();
+                        self.previous_time = None;
+                        self.refresh_time();
+                        self.save_config();
+                    }
+                    Err(why) => eprintln!("error importing settings from {}: {why}", path.display()),
+                }
+            }
+            // Half typed zone names keep the last valid zone, rather than falling back to the local one
+            Message::TimezoneInputChanged(text) => {
+                if is_valid_zone(&text) {
+                    self.config.timezone = text.trim().to_string();
+                    self.refresh_time();
+                    self.save_config();
+                }
+                self.timezone_input = text;
+            }
+            Message::SecondaryZoneInputChanged(text) => {
+                if is_valid_zone(&text) {
+                    self.config.secondary_zone = Some(text.trim().to_string()).filter(|zone| !zone.is_empty());
+                    self.save_config();
+                }
+                self.secondary_zone_input = text;
+            }
+            Message::AlarmInputChanged(text) => {
+                if text.trim().is_empty() {
+                    self.config.alarm_time = None;
+                    self.save_config();
+                } else if parse_alarm_time(&text).is_some() {
+                    self.config.alarm_time = Some(text.trim().to_string());
+                    // A new alarm time may still be due today
+                    self.alarm_fired = None;
+                    self.save_config();
+                }
+                self.alarm_input = text;
+            }
+            Message::SetPopupWidth(width) => {
+                self.config.popup_width = width;
+                self.save_config();
+            }
+            Message::SetPopupHeight(height) => {
+                self.config.popup_height = height;
+                self.save_config();
+            }
+            Message::SetEpochBits(bits) => {
+                self.config.epoch_bits = bits;
+                self.save_config();
+            }
+            // There are no popups outside the panel, so the settings go in the window itself
+            Message::TogglePopup | Message::OpenContextMenu if self.windowed => {
+                self.show_settings = !self.show_settings;
+            }
+            Message::TogglePopup => return self.toggle_popup(PopupKind::Settings),
+            Message::OpenContextMenu => return self.toggle_popup(PopupKind::ContextMenu),
+            Message::ShowTutorial => {
+                self.popup_kind = PopupKind::Tutorial;
+                self.tutorial_column = 0;
+            }
+            Message::TutorialColumn(index) => self.tutorial_column = index,
+            Message::CloseTutorial => self.popup_kind = PopupKind::Settings,
+            Message::EnterFullscreen => {
+                if self.overlay.is_some() {
+                    return Task::none();
+                }
+                let (id, opened) = window::open(window::Settings {
+                    decorations: false,
+                    ..Default::default()
+                });
+                self.overlay = Some(id);
+                // The popup would be left floating over the panel behind the overlay
+                let close_popup = self.popup.take().map_or_else(Task::none, destroy_popup);
+                return Task::batch([
+                    close_popup,
+                    opened.discard(),
+                    window::set_mode(id, window::Mode::Fullscreen),
+                ]);
+            }
+            Message::ExitFullscreen => {
+                if let Some(id) = self.overlay.take() {
+                    return window::close(id);
                 }
             }
             Message::PopupClosed(id) => {
                 if self.popup.as_ref() == Some(&id) {
                     self.popup = None;
+                    self.color_picker = None;
+                }
+                // Closed some other way than through the clock, like by the compositor
+                if self.overlay == Some(id) {
+                    self.overlay = None;
                 }
             }
         }
@@ -297,6 +1159,1201 @@ impl cosmic::Application for AppModel {
     }
 
     fn style(&self) -> Option<cosmic::iced_runtime::Appearance> {
+        if self.windowed {
+            return None;
+        }
         Some(cosmic::applet::style())
     }
 }
+
+impl AppModel {
+    /// Reads the clock again, unless the configuration has frozen it at a fixed time.
+    fn refresh_time(&mut self) {
+        self.current_time = self.config.frozen_time().unwrap_or_else(|| self.now_in_configured_zone());
+    }
+
+    /// The time now in the zone configured for the panel's output, or the system's own zone.
+    /// Everything that reads the clock goes through here, so they agree on the zone.
+    fn now_in_configured_zone(&self) -> DateTime<FixedOffset> {
+        in_configured_zone(&self.config, &self.output_name, Utc::now())
+    }
+
+    /// A canvas for `clock` in the panel, as long as it needs to be for the panel's thickness.
+    fn panel_canvas(&self, clock: BinaryClock<Message>) -> Canvas<BinaryClock<Message>, Message, cosmic::Theme, cosmic::Renderer> {
+        let horizontal = self.core.applet.is_horizontal();
+        let (width, height) = self.core.applet.suggested_size(false);
+        let thickness = if horizontal { height } else { width };
+        let size = clock.preferred_size(thickness.into(), horizontal);
+        canvas::Canvas::new(clock)
+            .width(Length::Fixed(size.width))
+            .height(Length::Fixed(size.height))
+    }
+
+    /// The clock with one column highlighted at a time, and how to add up the value it shows.
+    /// The place values are written in the circles, so the sum can be followed along. Clicking the
+    /// clock moves on to the next column.
+    fn tutorial_view(&self) -> Element<'_, Message> {
+        let count = self.clock_widget().column_count();
+        // The clock may have lost a column since, like the tens of hours going blank
+        let index = self.tutorial_column.min(count.saturating_sub(1));
+        let next = (index + 1) % count.max(1);
+        let clock = self
+            .clock_widget()
+            .show_place_values(true)
+            .highlight_column(Some(index))
+            .on_press(Message::TutorialColumn(next));
+        let explanation = match clock.column_value(index) {
+            Some((value, rows)) => {
+                let lit: Vec<String> = (0..rows)
+                    .filter(|row| (value >> row) & 1 == 1)
+                    .map(|row| (1u64 << row).to_string())
+                    .collect();
+                let sum = if lit.is_empty() { "0".to_string() } else { lit.join(" + ") };
+                fl!(
+                    "tutorial-column",
+                    column = (index + 1).to_string(),
+                    count = count.to_string(),
+                    sum = sum,
+                    value = value.to_string()
+                )
+            }
+            None => String::new(),
+        };
+        let canvas: Canvas<BinaryClock<Message>, Message, cosmic::Theme, cosmic::Renderer> =
+            canvas::Canvas::new(clock)
+                .width(Length::Fill)
+                .height(Length::Fixed(POPUP_CLOCK_HEIGHT));
+        let mut back = widget::button::standard(fl!("tutorial-back"));
+        if index > 0 {
+            back = back.on_press(Message::TutorialColumn(index - 1));
+        }
+        let forward = if index + 1 < count {
+            widget::button::suggested(fl!("tutorial-next")).on_press(Message::TutorialColumn(index + 1))
+        } else {
+            widget::button::suggested(fl!("tutorial-done")).on_press(Message::CloseTutorial)
+        };
+        let content = widget::column()
+            .push(widget::container(canvas).padding([8, 16]))
+            .push(widget::text::body(fl!("tutorial-intro")))
+            .push(widget::text::heading(explanation))
+            .push(widget::row().push(back).push(forward).spacing(8))
+            .spacing(12)
+            .padding([0, 16, 16, 16]);
+        self.core.applet.popup_container(content).into()
+    }
+
+    /// The clock filling the whole screen on the theme's background, like a bedside clock. Clicking
+    /// it closes it again, and so does Escape.
+    fn overlay_view(&self) -> Element<'_, Message> {
+        let clock: Canvas<BinaryClock<Message>, Message, cosmic::Theme, cosmic::Renderer> =
+            canvas::Canvas::new(self.clock_widget().on_press(Message::ExitFullscreen))
+                .width(Length::Fill)
+                .height(Length::Fill);
+        // Applet surfaces are see-through, which would leave the desktop showing between the circles
+        widget::container(clock)
+            .padding(32)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .class(cosmic::theme::Container::Background)
+            .into()
+    }
+
+    /// A large clock filling the window, with the settings below it when they are toggled on.
+    fn window_view(&self) -> Element<'_, Message> {
+        let clock: Canvas<BinaryClock<Message>, Message, cosmic::Theme, cosmic::Renderer> =
+            canvas::Canvas::new(self.clock_widget())
+                .width(Length::Fill)
+                .height(Length::Fill);
+
+        let mut content = widget::column().push(clock);
+        if self.show_settings {
+            content = content.push(widget::scrollable(self.settings_view()).height(Length::Fill));
+        }
+        content.into()
+    }
+
+    /// Every setting, shown in the popup or below the clock in window mode.
+    fn settings_view(&self) -> Element<'_, Message> {
+        let palette = self.clock_widget().palette(&cosmic::theme::active());
+        let mut content_list = widget::list_column()
+            .spacing(0)
+            .add(widget::settings::item(
+                fl!("current-time"),
+                widget::text::body(self.current_time.format("%H:%M:%S").to_string()),
+            ));
+
+        if self.config.show_date {
+            content_list = content_list.add(widget::settings::item(
+                fl!("current-date"),
+                widget::text::body(fl!(
+                    "date-format",
+                    weekday = weekday_label(self.current_time.weekday()),
+                    day = self.current_time.day(),
+                    month = month_label(self.current_time.month()),
+                    year = self.current_time.year()
+                )),
+            ));
+        }
+
+        if self.config.show_day_of_month {
+            // Too cramped for the panel, so it only shows here, as a row of five bits
+            let day: Canvas<BinaryClock<Message>, Message, cosmic::Theme, cosmic::Renderer> =
+                canvas::Canvas::new(self.clock_widget().day_of_month(true).orientation(Orientation::Vertical))
+                    .width(Length::Fixed(120.0))
+                    .height(Length::Fixed(32.0));
+            content_list = content_list.add(widget::settings::item(fl!("day-of-month"), day));
+        }
+
+        content_list = content_list.add(widget::settings::item(
+            fl!("display-mode"),
+            widget::dropdown(
+                &self.mode_labels,
+                DisplayMode::ALL.iter().position(|mode| *mode == self.config.mode),
+                |index| Message::SetDisplayMode(DisplayMode::ALL[index]),
+            ),
+        ));
+
+        if self.config.mode == DisplayMode::Stopwatch {
+            let toggle = if self.stopwatch_start.is_some() {
+                fl!("stopwatch-stop")
+            } else {
+                fl!("stopwatch-start")
+            };
+            content_list = content_list.add(widget::settings::item_row(vec![
+                widget::button::standard(toggle).on_press(Message::ToggleStopwatch).into(),
+                widget::button::standard(fl!("stopwatch-reset"))
+                    .on_press(Message::ResetStopwatch)
+                    .into(),
+            ]));
+        }
+
+        if self.config.mode == DisplayMode::Countdown {
+            let toggle = if self.countdown_end.is_some() {
+                fl!("countdown-pause")
+            } else {
+                fl!("stopwatch-start")
+            };
+            content_list = content_list
+                .add(widget::settings::item_row(vec![
+                    widget::button::standard(toggle).on_press(Message::ToggleCountdown).into(),
+                    widget::button::standard(fl!("stopwatch-reset"))
+                        .on_press(Message::ResetCountdown)
+                        .into(),
+                ]))
+                .add(widget::settings::item(
+                    fl!("countdown-minutes"),
+                    widget::spin_button(
+                        self.config.countdown_minutes.to_string(),
+                        self.config.countdown_minutes,
+                        1,
+                        COUNTDOWN_MINUTES_RANGE.0,
+                        COUNTDOWN_MINUTES_RANGE.1,
+                        Message::SetCountdownMinutes,
+                    ),
+                ));
+        }
+
+        if self.config.mode == DisplayMode::UnixEpoch {
+            content_list = content_list.add(widget::settings::item(
+                fl!("epoch-bits"),
+                widget::spin_button(
+                    self.config.epoch_bits().to_string(),
+                    u32::from(self.config.epoch_bits()),
+                    EPOCH_BITS_STEP,
+                    EPOCH_BITS_RANGE.0,
+                    EPOCH_BITS_RANGE.1,
+                    Message::SetEpochBits,
+                ),
+            ));
+        }
+
+        let mut content_list = content_list
+            .add(widget::settings::item(
+                fl!("alignment"),
+                widget::dropdown(
+                    &self.alignment_labels,
+                    ClockAlignment::ALL.iter().position(|alignment| *alignment == self.config.alignment),
+                    |index| Message::SetAlignment(ClockAlignment::ALL[index]),
+                ),
+            ))
+            .add(widget::settings::item(
+                fl!("cell-shape"),
+                widget::dropdown(
+                    &self.shape_labels,
+                    CellShape::ALL.iter().position(|shape| *shape == self.config.cell_shape),
+                    |index| Message::SetCellShape(CellShape::ALL[index]),
+                ),
+            ))
+            .add(widget::settings::item(
+                fl!("cell-spacing"),
+                widget::slider(0.0..=1.0, self.config.cell_spacing, Message::SetCellSpacing).step(0.05),
+            ))
+            .add(widget::settings::item(
+                fl!("group-gap"),
+                widget::slider(0.0..=MAX_GROUP_GAP, self.config.group_gap.clamp(0.0, MAX_GROUP_GAP), Message::SetGroupGap)
+                    .step(0.1),
+            ))
+            .add(widget::settings::item(
+                fl!("scale"),
+                widget::slider(MIN_SCALE..=MAX_SCALE, self.config.scale.clamp(MIN_SCALE, MAX_SCALE), Message::SetScale)
+                    .step(0.05),
+            ))
+            .add(widget::settings::item(
+                fl!("padding"),
+                widget::slider(0.0..=MAX_PADDING, self.config.padding.clamp(0.0, MAX_PADDING), Message::SetPadding)
+                    .step(0.05),
+            ))
+            .add(widget::settings::item(
+                fl!("side-padding"),
+                widget::slider(0.0..=MAX_SIDE_PADDING, self.config.side_padding.clamp(0.0, MAX_SIDE_PADDING), Message::SetSidePadding)
+                    .step(1.0),
+            ))
+            .add(widget::settings::item(
+                fl!("brightness"),
+                widget::slider(0.0..=1.0, self.config.brightness.clamp(0.0, 1.0), Message::SetBrightness).step(0.05),
+            ))
+            .add(widget::settings::item(
+                fl!("dim-when-idle"),
+                widget::toggler(self.config.dim_when_idle).on_toggle(Message::ToggleDimWhenIdle),
+            ))
+            .add(widget::settings::item(
+                fl!("night-mode"),
+                widget::toggler(self.config.night_mode).on_toggle(Message::ToggleNightMode),
+            ))
+            .add(widget::settings::item(
+                fl!("night-start"),
+                widget::spin_button(
+                    hour_label(self.config.night_start),
+                    self.config.night_start,
+                    1,
+                    0,
+                    23,
+                    Message::SetNightStart,
+                ),
+            ))
+            .add(widget::settings::item(
+                fl!("night-end"),
+                widget::spin_button(
+                    hour_label(self.config.night_end),
+                    self.config.night_end,
+                    1,
+                    0,
+                    23,
+                    Message::SetNightEnd,
+                ),
+            ))
+            .add(widget::settings::item(
+                fl!("inactive-style"),
+                widget::dropdown(
+                    &self.inactive_style_labels,
+                    InactiveStyle::ALL.iter().position(|style| *style == self.config.inactive_style),
+                    |index| Message::SetInactiveStyle(InactiveStyle::ALL[index]),
+                ),
+            ))
+            .add(widget::settings::item(
+                fl!("lsb-first"),
+                widget::toggler(self.config.bit_order == BitOrder::LsbTop).on_toggle(|lsb_top| {
+                    Message::SetBitOrder(if lsb_top { BitOrder::LsbTop } else { BitOrder::MsbTop })
+                }),
+            ))
+            .add(widget::settings::item(
+                fl!("follow-system-hour-format"),
+                widget::toggler(self.config.follow_system_hour_format)
+                    .on_toggle(Message::ToggleFollowSystemHourFormat),
+            ))
+            .add(widget::settings::item(
+                fl!("twelve-hour"),
+                widget::toggler(self.twelve_hour()).on_toggle_maybe(
+                    (!self.config.follow_system_hour_format).then_some(Message::ToggleTwelveHour),
+                ),
+            ))
+            .add(widget::settings::item(
+                fl!("blank-leading-zero"),
+                widget::toggler(self.config.blank_leading_zero).on_toggle(Message::ToggleBlankLeadingZero),
+            ))
+            .add(widget::settings::item(
+                fl!("meridiem-dot"),
+                widget::toggler(self.config.meridiem_style == MeridiemStyle::CornerDot).on_toggle(|dot| {
+                    Message::SetMeridiemStyle(if dot { MeridiemStyle::CornerDot } else { MeridiemStyle::Column })
+                }),
+            ))
+            .add(widget::settings::item(
+                fl!("show-seconds"),
+                widget::toggler(self.config.show_seconds).on_toggle(Message::ToggleShowSeconds),
+            ))
+            .add(widget::settings::item(
+                fl!("seconds-as-ring"),
+                widget::toggler(self.config.seconds_as_ring).on_toggle(Message::ToggleSecondsAsRing),
+            ))
+            .add(widget::settings::item(
+                fl!("show-deciseconds"),
+                widget::toggler(self.config.show_deciseconds).on_toggle(Message::ToggleShowDeciseconds),
+            ))
+            .add(widget::settings::item(
+                fl!("show-labels"),
+                widget::toggler(self.config.show_labels).on_toggle(Message::ToggleShowLabels),
+            ))
+            .add(widget::settings::item(
+                fl!("blink-separator"),
+                widget::toggler(self.config.blink_separator).on_toggle(Message::ToggleBlinkSeparator),
+            ))
+            .add(widget::settings::item(
+                fl!("mirror"),
+                widget::toggler(self.config.mirror).on_toggle(Message::ToggleMirror),
+            ))
+            .add(widget::settings::item(
+                fl!("show-date"),
+                widget::toggler(self.config.show_date).on_toggle(Message::ToggleShowDate),
+            ))
+            .add(widget::settings::item(
+                fl!("show-day-of-month"),
+                widget::toggler(self.config.show_day_of_month).on_toggle(Message::ToggleShowDayOfMonth),
+            ))
+            .add(widget::settings::item(
+                fl!("show-digit-labels"),
+                widget::toggler(self.config.show_digit_labels).on_toggle(Message::ToggleShowDigitLabels),
+            ))
+            .add(widget::settings::item(
+                fl!("show-weekday"),
+                widget::toggler(self.config.show_weekday).on_toggle(Message::ToggleShowWeekday),
+            ));
+
+        if self.config.show_weekday {
+            content_list = content_list
+                .add(widget::settings::item(
+                    fl!("label-weekdays"),
+                    widget::toggler(self.config.label_weekdays).on_toggle(Message::ToggleLabelWeekdays),
+                ))
+                .add(widget::settings::item(
+                    fl!("week-starts-on"),
+                    widget::dropdown(
+                        &self.week_start_labels,
+                        WeekStart::ALL.iter().position(|week_start| *week_start == self.config.week_starts_on),
+                        |index| Message::SetWeekStart(WeekStart::ALL[index]),
+                    ),
+                ));
+        }
+
+        content_list = content_list
+            .add(widget::settings::item(
+                fl!("show-day-progress"),
+                widget::toggler(self.config.show_day_progress).on_toggle(Message::ToggleShowDayProgress),
+            ))
+            .add(widget::settings::item(
+                fl!("compact-panel"),
+                widget::toggler(self.config.compact_panel).on_toggle(Message::ToggleCompactPanel),
+            ))
+            .add(widget::settings::item(
+                fl!("show-background"),
+                widget::toggler(self.config.show_background).on_toggle(Message::ToggleShowBackground),
+            ))
+            .add(widget::settings::item(
+                fl!("vertical-layout"),
+                widget::toggler(self.config.orientation == Orientation::Vertical).on_toggle(|vertical| {
+                    Message::SetOrientation(if vertical {
+                        Orientation::Vertical
+                    } else {
+                        Orientation::Horizontal
+                    })
+                }),
+            ))
+            .add(widget::settings::item(
+                fl!("heartbeat"),
+                widget::toggler(self.config.heartbeat).on_toggle(Message::ToggleHeartbeat),
+            ))
+            .add(widget::settings::item(
+                fl!("minute-pulse"),
+                widget::toggler(self.config.minute_pulse).on_toggle(Message::ToggleMinutePulse),
+            ))
+            .add(widget::settings::item(
+                fl!("hourly-chime"),
+                widget::toggler(self.config.hourly_chime).on_toggle(Message::ToggleHourlyChime),
+            ))
+            .add(widget::settings::item(fl!("alarm"), self.alarm_entry()))
+            .add(widget::settings::item(
+                fl!("animations"),
+                widget::toggler(self.config.animations).on_toggle(Message::ToggleAnimations),
+            ))
+            .add(widget::settings::item(
+                fl!("place-value-tint"),
+                widget::toggler(self.config.place_value_tint).on_toggle(Message::TogglePlaceValueTint),
+            ))
+            .add(widget::settings::item(
+                fl!("show-place-values"),
+                widget::toggler(self.config.show_place_values).on_toggle(Message::ToggleShowPlaceValues),
+            ))
+            .add(widget::settings::item(
+                fl!("high-contrast"),
+                widget::toggler(self.config.high_contrast).on_toggle(Message::ToggleHighContrast),
+            ))
+            .add(widget::settings::item(
+                fl!("invert"),
+                widget::toggler(self.config.invert).on_toggle(Message::ToggleInvert),
+            ))
+            .add(widget::settings::item(
+                fl!("cell-border"),
+                widget::toggler(self.config.cell_border).on_toggle(Message::ToggleCellBorder),
+            ))
+            .add(widget::settings::item(
+                fl!("preset"),
+                widget::dropdown(&self.preset_labels, None, |index| Message::ApplyPreset(Preset::ALL[index])),
+            ))
+            .add(widget::settings::item(
+                fl!("active-color"),
+                color_entry(
+                    palette.active,
+                    ColorTarget::Active,
+                    self.color_picker.as_ref(),
+                    &self.active_color_input,
+                    |text| match parse_hex_color(&text) {
+                        Some(color) => Message::SetActiveColor(color),
+                        None => Message::ActiveColorInput(text),
+                    },
+                    Message::SetActiveColor,
+                ),
+            ))
+            .add(widget::settings::item(
+                fl!("inactive-color"),
+                color_entry(
+                    palette.inactive,
+                    ColorTarget::Inactive,
+                    self.color_picker.as_ref(),
+                    &self.inactive_color_input,
+                    |text| match parse_hex_color(&text) {
+                        Some(color) => Message::SetInactiveColor(color),
+                        None => Message::InactiveColorInput(text),
+                    },
+                    Message::SetInactiveColor,
+                ),
+            ))
+            .add(widget::settings::item(
+                fl!("per-field-colors"),
+                widget::toggler(self.config.per_field_colors).on_toggle(Message::TogglePerFieldColors),
+            ));
+
+        if self.config.per_field_colors {
+            let colors = self.clock_widget().field_palette_colors(&cosmic::theme::active());
+            let labels = [fl!("hour-color"), fl!("minute-color"), fl!("second-color")];
+            for (field, (label, color)) in labels.into_iter().zip(colors).enumerate() {
+                content_list = content_list.add(widget::settings::item(
+                    label,
+                    color_entry(
+                        color,
+                        ColorTarget::Field(field),
+                        self.color_picker.as_ref(),
+                        &self.field_color_inputs[field],
+                        move |text| match parse_hex_color(&text) {
+                            Some(color) => Message::SetFieldColor(field, color),
+                            None => Message::FieldColorInput(field, text),
+                        },
+                        move |color| Message::SetFieldColor(field, color),
+                    ),
+                ));
+            }
+        }
+
+        content_list = content_list.add(widget::settings::item(
+            fl!("gradient"),
+            widget::toggler(self.config.gradient).on_toggle(Message::ToggleGradient),
+        ));
+
+        if self.config.gradient {
+            let colors = self.clock_widget().gradient_palette_colors(&cosmic::theme::active());
+            let labels = [fl!("gradient-start"), fl!("gradient-end")];
+            for (end, (label, color)) in labels.into_iter().zip(colors).enumerate() {
+                content_list = content_list.add(widget::settings::item(
+                    label,
+                    color_entry(
+                        color,
+                        ColorTarget::Gradient(end),
+                        self.color_picker.as_ref(),
+                        &self.gradient_color_inputs[end],
+                        move |text| match parse_hex_color(&text) {
+                            Some(color) => Message::SetGradientColor(end, color),
+                            None => Message::GradientColorInput(end, text),
+                        },
+                        move |color| Message::SetGradientColor(end, color),
+                    ),
+                ));
+            }
+        }
+
+        let content_list = content_list
+            .add(widget::settings::item_row(vec![
+                widget::button::standard(fl!("use-theme-colors"))
+                    .on_press(Message::UseThemeColors)
+                    .into(),
+            ]))
+            .add(widget::settings::item(
+                fl!("timezone"),
+                zone_entry(fl!("system-timezone"), &self.timezone_input, Message::TimezoneInputChanged),
+            ))
+            .add(widget::settings::item(
+                fl!("secondary-zone"),
+                zone_entry(fl!("no-secondary-zone"), &self.secondary_zone_input, Message::SecondaryZoneInputChanged),
+            ))
+            .add(widget::settings::item(
+                fl!("popup-width"),
+                widget::spin_button(
+                    self.config.popup_width.to_string(),
+                    self.config.popup_width,
+                    POPUP_SIZE_STEP,
+                    POPUP_WIDTH_RANGE.0,
+                    POPUP_WIDTH_RANGE.1,
+                    Message::SetPopupWidth,
+                ),
+            ))
+            .add(widget::settings::item(
+                fl!("popup-height"),
+                widget::spin_button(
+                    self.config.popup_height.to_string(),
+                    self.config.popup_height,
+                    POPUP_SIZE_STEP,
+                    POPUP_HEIGHT_RANGE.0,
+                    POPUP_HEIGHT_RANGE.1,
+                    Message::SetPopupHeight,
+                ),
+            ))
+            .add(widget::settings::item_row(vec![
+                widget::button::standard(fl!("import-settings"))
+                    .on_press(Message::ChooseImportPath)
+                    .into(),
+                widget::button::standard(fl!("export-settings"))
+                    .on_press(Message::ChooseExportPath)
+                    .into(),
+            ]))
+            .add(widget::settings::item_row(vec![
+                widget::button::standard(fl!("export-image"))
+                    .on_press(Message::ChooseImagePath)
+                    .into(),
+                widget::button::destructive(fl!("reset-settings"))
+                    .on_press(Message::ResetConfig)
+                    .into(),
+            ]));
+        let content_list = match &self.image_export {
+            Some(Ok(path)) => {
+                content_list.add(widget::text::caption(fl!("image-saved", path = path.display().to_string())))
+            }
+            Some(Err(why)) => content_list.add(widget::text::caption(fl!("image-not-saved", error = why.clone()))),
+            None => content_list,
+        };
+
+        content_list.into()
+    }
+
+    /// Closes the open popup, or opens one of the given kind if none is open.
+    fn toggle_popup(&mut self, kind: PopupKind) -> Task<cosmic::Action<Message>> {
+        if let Some(p) = self.popup.take() {
+            return destroy_popup(p);
+        }
+
+        // The applet has no surface of its own while the panel is still starting up or
+        // reconfiguring its outputs, which leaves nothing to anchor the popup to. Panicking
+        // would take the applet out of the panel for good, so just leave the popup closed.
+        let Some(main_window_id) = self.core.main_window_id() else {
+            tracing::debug!("not opening popup, the applet has no main window");
+            return Task::none();
+        };
+
+        let new_id = Id::unique();
+        self.popup.replace(new_id);
+        self.popup_kind = kind;
+        let mut popup_settings = self.core.applet.get_popup_settings(
+            main_window_id,
+            new_id,
+            None,
+            None,
+            None,
+        );
+        let (width, height) = self.config.popup_size();
+        popup_settings.positioner.size_limits = match kind {
+            PopupKind::Settings | PopupKind::Tutorial => Limits::NONE
+                .max_width(width)
+                .min_width(POPUP_WIDTH_RANGE.0 as f32)
+                .min_height(POPUP_HEIGHT_RANGE.0 as f32)
+                .max_height(height),
+            PopupKind::ContextMenu => Limits::NONE
+                .max_width(300.0)
+                .min_width(200.0)
+                .min_height(50.0)
+                .max_height(1080.0),
+        };
+        get_popup(popup_settings)
+    }
+
+    /// Quick toggles for the settings people change most, shown on right click.
+    fn context_menu(&self) -> Element<'_, Message> {
+        let mut menu = widget::column()
+            .padding([8, 0])
+            .push(cosmic::applet::padded_control(
+                widget::toggler(self.config.show_seconds)
+                    .label(fl!("show-seconds"))
+                    .on_toggle(Message::ToggleShowSeconds),
+            ))
+            .push(cosmic::applet::padded_control(
+                widget::toggler(self.twelve_hour())
+                    .label(fl!("twelve-hour"))
+                    .on_toggle_maybe((!self.config.follow_system_hour_format).then_some(Message::ToggleTwelveHour)),
+            ))
+            .push(cosmic::applet::padded_control(widget::divider::horizontal::default()));
+
+        for (mode, label) in DisplayMode::ALL.iter().zip(&self.mode_labels) {
+            let mut item = widget::row()
+                .push(widget::text::body(label.as_str()))
+                .push(widget::horizontal_space())
+                .align_y(Alignment::Center);
+            if *mode == self.config.mode {
+                item = item.push(widget::icon::from_name("object-select-symbolic").size(16));
+            }
+            menu = menu.push(cosmic::applet::menu_button(item).on_press(Message::SetDisplayMode(*mode)));
+        }
+
+        self.core.applet.popup_container(menu).into()
+    }
+
+    /// Switches the display mode, starting the stopwatch afresh when switching to it, and
+    /// setting the countdown back to its full length, ready to start.
+    fn set_mode(&mut self, mode: DisplayMode) {
+        if mode == DisplayMode::Stopwatch && self.config.mode != mode {
+            self.stopwatch_elapsed = Duration::ZERO;
+            self.stopwatch_start = Some(Instant::now());
+        }
+        if mode == DisplayMode::Countdown && self.config.mode != mode {
+            self.countdown_end = None;
+            self.countdown_left = self.config.countdown();
+        }
+        self.config.mode = mode;
+        self.save_config();
+    }
+
+    /// The time on the stopwatch.
+    fn stopwatch(&self) -> Duration {
+        self.stopwatch_elapsed + self.stopwatch_start.map_or(Duration::ZERO, |start| start.elapsed())
+    }
+
+    /// How often the clock ticks, in milliseconds. Nobody watches an idle session's clock, so
+    /// then only once a minute.
+    fn tick_period_ms(&self) -> u32 {
+        if self.idle { 60_000 } else { self.config.tick_period_ms() }
+    }
+
+    /// The time left on the countdown, which stays at zero once it is over.
+    fn countdown(&self) -> Duration {
+        match self.countdown_end {
+            Some(end) => end.saturating_duration_since(Instant::now()),
+            None => self.countdown_left,
+        }
+    }
+
+    /// Whether the countdown has run out and hasn't been reset since.
+    fn countdown_finished(&self) -> bool {
+        self.countdown_end.is_none() && self.countdown_left.is_zero()
+    }
+
+    /// Whether hours are shown as 1-12, following the desktop when asked to.
+    fn twelve_hour(&self) -> bool {
+        if self.config.follow_system_hour_format {
+            self.system_twelve_hour
+        } else {
+            self.config.twelve_hour
+        }
+    }
+
+    /// The displayed time as plain text, for anyone who can't read binary yet.
+    fn time_label(&self) -> String {
+        let time = if self.twelve_hour() {
+            self.current_time.format("%-I:%M %p")
+        } else {
+            self.current_time.format("%H:%M")
+        };
+        time.to_string()
+    }
+
+    /// The displayed time in words, for screen readers to announce in place of the canvas.
+    fn accessible_label(&self) -> String {
+        fl!("accessible-time", time = spoken_time(self.current_time.time(), self.twelve_hour()))
+    }
+
+    /// The clock as currently configured.
+    fn clock_widget(&self) -> BinaryClock<Message> {
+        BinaryClock::new(self.current_time)
+            .mode(self.config.mode)
+            .orientation(self.config.orientation)
+            .alignment(self.config.alignment)
+            .cell_shape(self.config.cell_shape)
+            .cell_spacing(self.config.cell_spacing)
+            .group_gap(self.config.group_gap)
+            .scale(self.config.scale)
+            .padding(self.config.padding)
+            .side_padding(self.config.side_padding)
+            .inactive_style(self.config.inactive_style)
+            .bit_order(self.config.bit_order)
+            .elapsed(self.stopwatch())
+            .remaining(self.countdown())
+            .epoch_bits(self.config.epoch_bits())
+            .twelve_hour(self.twelve_hour())
+            .meridiem_style(self.config.meridiem_style)
+            .show_seconds(self.config.show_seconds)
+            .seconds_as_ring(self.config.seconds_as_ring)
+            .show_deciseconds(self.config.show_deciseconds)
+            .mirror(self.config.mirror)
+            .show_labels(self.config.show_labels)
+            .blink_separator(self.config.blink_separator && !self.reduced_motion)
+            .heartbeat(self.heartbeat)
+            .minute_pulse(self.minute_pulse)
+            .fade_from(self.previous_time, self.fade)
+            .active_color(self.config.active_color())
+            .inactive_color(self.config.inactive_color())
+            .field_colors(self.config.field_colors())
+            .gradient(self.config.gradient_colors())
+            .place_value_tint(self.config.place_value_tint)
+            .show_place_values(self.config.show_place_values)
+            .high_contrast(self.config.high_contrast)
+            .invert(self.config.invert)
+            .cell_border(self.config.cell_border)
+            .blank_leading_zero(self.config.blank_leading_zero)
+            .show_background(self.config.show_background)
+            .show_digit_labels(self.config.show_digit_labels)
+            .show_weekday(self.config.show_weekday)
+            .weekday_labels(self.config.label_weekdays.then(weekday_initials))
+            .first_weekday(self.config.week_starts_on.weekday())
+            .show_day_progress(self.config.show_day_progress)
+            .brightness(if self.idle || self.config.is_night(self.current_time.hour()) {
+                0.0
+            } else {
+                self.config.brightness
+            })
+            .on_press(Message::TogglePopup)
+            .on_right_press(Message::OpenContextMenu)
+    }
+
+    /// Whether the alarm time came up between the tick at `previous_time` and this one, and the
+    /// alarm hasn't gone off yet today. Marks it as gone off if so.
+    ///
+    /// Checked as a range rather than an exact match, because ticks only come once a minute while
+    /// seconds are hidden and can skip ahead after a suspend.
+    fn alarm_due(&mut self, previous_time: DateTime<FixedOffset>) -> bool {
+        let Some(alarm) = self.config.alarm_time() else {
+            return false;
+        };
+        let today = self.current_time.date_naive();
+        let due = today.and_time(alarm);
+        if self.alarm_fired == Some(today)
+            || !(previous_time.naive_local() < due && due <= self.current_time.naive_local())
+        {
+            return false;
+        }
+        self.alarm_fired = Some(today);
+        true
+    }
+
+    /// An entry for the alarm time, with a hint below it while the time can't be read.
+    fn alarm_entry(&self) -> Element<'_, Message> {
+        let mut entry = widget::column()
+            .push(
+                widget::text_input(fl!("no-alarm"), &self.alarm_input)
+                    .on_input(Message::AlarmInputChanged)
+                    .width(180),
+            )
+            .spacing(4);
+        if !self.alarm_input.trim().is_empty() && parse_alarm_time(&self.alarm_input).is_none() {
+            entry = entry.push(widget::text::caption(fl!("invalid-alarm-time")));
+        }
+        entry.into()
+    }
+
+    /// Writes the current configuration through cosmic-config so it survives a restart.
+    fn save_config(&self) {
+        if let Some(handler) = &self.config_handler {
+            if let Err(why) = self.config.write_entry(handler) {
+                eprintln!("error saving config: {why}");
+            }
+        }
+    }
+}
+
+/// Ticks every `period_ms` milliseconds, right as the wall clock crosses a multiple of the period.
+///
+/// The wait for the next boundary is worked out from the wall clock before every tick rather than
+/// once up front, so the ticks stay on the boundary after a suspend or a clock adjustment.
+fn aligned_ticks(period_ms: u32) -> Subscription<Message> {
+    struct AlignedTicks;
+
+    Subscription::run_with_id(
+        (std::any::TypeId::of::<AlignedTicks>(), period_ms),
+        cosmic::iced::stream::channel(1, move |mut channel| async move {
+            let period_ms = i64::from(period_ms);
+            loop {
+                let into_period = Local::now().timestamp_millis().rem_euclid(period_ms);
+                tokio::time::sleep(tokio::time::Duration::from_millis((period_ms - into_period) as u64)).await;
+                if channel.send(Message::Tick).await.is_err() {
+                    break;
+                }
+            }
+        }),
+    )
+}
+
+/// The message for a keyboard shortcut:
+///
+/// - Space opens or closes the settings popup
+/// - M switches to the next display mode
+/// - Escape closes the fullscreen clock
+fn shortcut(key: Key, modifiers: Modifiers) -> Option<Message> {
+    // Leave combinations with modifiers to the compositor and other applications
+    if !modifiers.is_empty() {
+        return None;
+    }
+    match key.as_ref() {
+        Key::Named(Named::Space) => Some(Message::TogglePopup),
+        Key::Character("m" | "M") => Some(Message::CycleMode),
+        Key::Named(Named::Escape) => Some(Message::ExitFullscreen),
+        _ => None,
+    }
+}
+
+/// Sends a desktop notification, like the one announcing a new hour or the alarm, without holding
+/// up the update loop.
+fn notify(summary: String) -> Task<cosmic::Action<Message>> {
+    Task::future(async move {
+        if let Err(why) = send_notification(&summary).await {
+            eprintln!("error sending notification: {why}");
+        }
+        cosmic::Action::None
+    })
+}
+
+/// Asks the desktop's notification server to show `summary`, for as long as it sees fit.
+async fn send_notification(summary: &str) -> zbus::Result<()> {
+    let connection = zbus::Connection::session().await?;
+    let actions: &[&str] = &[];
+    let hints: HashMap<&str, zbus::zvariant::Value<'_>> = HashMap::new();
+    connection
+        .call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.Notifications"),
+            "Notify",
+            // Application name, id of a notification to replace, icon, summary, body, actions, hints and timeout
+            &(fl!("app-title"), 0u32, "alarm-symbolic", summary, "", actions, hints, -1i32),
+        )
+        .await?;
+    Ok(())
+}
+
+/// `instant` in the zone `config` has for the panel on `output`, or in the system's own zone.
+fn in_configured_zone(config: &Config, output: &str, instant: DateTime<Utc>) -> DateTime<FixedOffset> {
+    // A named zone rather than a fixed offset, so the clock follows daylight saving time
+    match config.timezone_for(output) {
+        Some(zone) => instant.with_timezone(&zone).fixed_offset(),
+        None => instant.with_timezone(&Local).fixed_offset(),
+    }
+}
+
+/// Whether the time moving on by `moved_ms` from one tick to the next, `period_ms` apart, is more
+/// than ticking explains, like after a suspend or the clock being set.
+///
+/// Ticks can come early, like the one right after starting or the first one on a wall clock
+/// boundary, so only moving backwards or further than a tick counts.
+fn is_clock_jump(moved_ms: i64, period_ms: u32) -> bool {
+    moved_ms < -CLOCK_JUMP_MS || moved_ms > i64::from(period_ms) + CLOCK_JUMP_MS
+}
+
+/// Whether the desktop shows twelve hour times, going by the settings of the panel's own clock.
+///
+/// Falls back to twenty-four hours when that can't be read.
+fn system_twelve_hour() -> bool {
+    cosmic_config::Config::new(SYSTEM_TIME_CONFIG_ID, 1)
+        .and_then(|config| config.get::<bool>("military_time"))
+        .is_ok_and(|military_time| !military_time)
+}
+
+/// A swatch of the color in use next to an entry for a `#rrggbb` or `#rrggbbaa` replacement,
+/// and a slider for its opacity. Clicking the swatch opens a color picker below them, unless
+/// `picker` is already open for `target`, in which case it is shown and the click closes it.
+fn color_entry<'a>(
+    color: Color,
+    target: ColorTarget,
+    picker: Option<&'a (ColorTarget, ColorPickerModel)>,
+    input: &'a str,
+    on_input: impl Fn(String) -> Message + 'a,
+    on_color: impl Fn(Color) -> Message + 'a,
+) -> Element<'a, Message> {
+    let swatch = widget::container(widget::Space::new(24, 24)).class(
+        cosmic::theme::Container::custom(move |_theme| widget::container::Style {
+            background: Some(Background::Color(color)),
+            border: Border {
+                radius: 12.0.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }),
+    );
+
+    let swatch = widget::button::custom(swatch)
+        .padding(0)
+        .on_press(Message::OpenColorPicker(target, color));
+
+    let row = widget::row()
+        .push(swatch)
+        .push(
+            widget::text_input(fl!("theme-color"), input)
+                .on_input(on_input)
+                .width(96),
+        )
+        .push(
+            widget::slider(0.0..=1.0, color.a, move |alpha| on_color(Color { a: alpha, ..color }))
+                .step(0.05)
+                .width(64),
+        )
+        .spacing(8)
+        .align_y(Alignment::Center);
+
+    match picker.filter(|(open, _)| *open == target) {
+        Some((_, picker)) => widget::column()
+            .push(row)
+            .push(
+                picker
+                    .builder(Message::ColorPicker)
+                    .width(Length::Fixed(248.0))
+                    .height(Length::Fixed(158.0))
+                    .build(fl!("recent-colors"), fl!("copy-to-clipboard"), fl!("copied-to-clipboard")),
+            )
+            .spacing(8)
+            .into(),
+        None => row.into(),
+    }
+}
+
+/// Whether `text` can be saved as a time zone: empty, for the default, or a known IANA zone name.
+fn is_valid_zone(text: &str) -> bool {
+    let text = text.trim();
+    text.is_empty() || text.parse::<Tz>().is_ok()
+}
+
+/// An entry for an IANA time zone name, with a hint below it while the name isn't a known zone.
+fn zone_entry<'a>(
+    placeholder: String,
+    input: &'a str,
+    on_input: impl Fn(String) -> Message + 'a,
+) -> Element<'a, Message> {
+    let mut entry = widget::column()
+        .push(widget::text_input(placeholder, input).on_input(on_input).width(180))
+        .spacing(4);
+    if !is_valid_zone(input) {
+        entry = entry.push(widget::text::caption(fl!("invalid-timezone")));
+    }
+    entry.into()
+}
+
+/// The text for the gradient's start and end color entries, as saved in `config`.
+fn gradient_inputs(config: &Config) -> [String; 2] {
+    [&config.gradient_start, &config.gradient_end].map(|color| color.clone().unwrap_or_default())
+}
+
+/// The localized name of a cell shape, as shown in the settings.
+fn shape_label(shape: CellShape) -> String {
+    match shape {
+        CellShape::Circle => fl!("shape-circle"),
+        CellShape::Square => fl!("shape-square"),
+        CellShape::RoundedSquare => fl!("shape-rounded-square"),
+    }
+}
+
+/// The localized name of an alignment, as shown in the settings.
+fn alignment_label(alignment: ClockAlignment) -> String {
+    match alignment {
+        ClockAlignment::Left => fl!("align-left"),
+        ClockAlignment::Center => fl!("align-center"),
+        ClockAlignment::Right => fl!("align-right"),
+    }
+}
+
+/// The localized name of an inactive cell style, as shown in the settings.
+fn inactive_style_label(style: InactiveStyle) -> String {
+    match style {
+        InactiveStyle::Filled => fl!("inactive-filled"),
+        InactiveStyle::Outline => fl!("inactive-outline"),
+        InactiveStyle::Hidden => fl!("inactive-hidden"),
+    }
+}
+
+/// The localized name of a color preset, as shown in the settings.
+fn preset_label(preset: Preset) -> String {
+    match preset {
+        Preset::Matrix => fl!("preset-matrix"),
+        Preset::AmberCrt => fl!("preset-amber-crt"),
+        Preset::IceBlue => fl!("preset-ice-blue"),
+    }
+}
+
+/// The first letter of every day of the week, Monday first, for the weekday strip.
+fn weekday_initials() -> [String; 7] {
+    [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun]
+        .map(|weekday| weekday_label(weekday).chars().take(1).collect())
+}
+
+/// The localized abbreviation of a day of the week.
+fn weekday_label(weekday: Weekday) -> String {
+    match weekday {
+        Weekday::Mon => fl!("weekday-mon"),
+        Weekday::Tue => fl!("weekday-tue"),
+        Weekday::Wed => fl!("weekday-wed"),
+        Weekday::Thu => fl!("weekday-thu"),
+        Weekday::Fri => fl!("weekday-fri"),
+        Weekday::Sat => fl!("weekday-sat"),
+        Weekday::Sun => fl!("weekday-sun"),
+    }
+}
+
+/// The localized abbreviation of a month, numbered from 1 for January.
+fn month_label(month: u32) -> String {
+    match month {
+        1 => fl!("month-jan"),
+        2 => fl!("month-feb"),
+        3 => fl!("month-mar"),
+        4 => fl!("month-apr"),
+        5 => fl!("month-may"),
+        6 => fl!("month-jun"),
+        7 => fl!("month-jul"),
+        8 => fl!("month-aug"),
+        9 => fl!("month-sep"),
+        10 => fl!("month-oct"),
+        11 => fl!("month-nov"),
+        _ => fl!("month-dec"),
+    }
+}
+
+/// Formats the start of an hour as `hh:00`.
+fn hour_label(hour: u32) -> String {
+    format!("{hour:02}:00")
+}
+
+/// The time of day in words, the way it is said out loud, like "three forty-two PM" or
+/// "fifteen oh five".
+fn spoken_time(time: NaiveTime, twelve_hour: bool) -> String {
+    let (pm, hour12) = time.hour12();
+    let hour = fl!("number-word", number = if twelve_hour { hour12 } else { time.hour() });
+    let minute = fl!("number-word", number = time.minute());
+    let spoken = match time.minute() {
+        0 if twelve_hour => fl!("spoken-time-twelve-hour-on-the-hour", hour = hour),
+        0 => fl!("spoken-time-twenty-four-hour-on-the-hour", hour = hour),
+        1..10 => fl!("spoken-time-oh", hour = hour, minute = minute),
+        _ => fl!("spoken-time", hour = hour, minute = minute),
+    };
+    if !twelve_hour {
+        return spoken;
+    }
+    let period = if pm { fl!("time-pm") } else { fl!("time-am") };
+    fl!("spoken-time-with-period", time = spoken, period = period)
+}
+
+/// The localized name of a week start, as shown in the settings.
+fn week_start_label(week_start: WeekStart) -> String {
+    match week_start {
+        WeekStart::Locale => fl!("week-start-locale"),
+        WeekStart::Monday => fl!("week-start-monday"),
+        WeekStart::Sunday => fl!("week-start-sunday"),
+    }
+}
+
+/// The localized name of a display mode, as shown in the settings.
+fn mode_label(mode: DisplayMode) -> String {
+    match mode {
+        DisplayMode::BCD => fl!("mode-bcd"),
+        DisplayMode::BinaryHMS => fl!("mode-binary"),
+        DisplayMode::BinarySeconds => fl!("mode-binary-seconds"),
+        DisplayMode::HEX => fl!("mode-hex"),
+        DisplayMode::Mixed => fl!("mode-mixed"),
+        DisplayMode::CompactRow => fl!("mode-compact-row"),
+        DisplayMode::Stopwatch => fl!("mode-stopwatch"),
+        DisplayMode::UnixEpoch => fl!("mode-unix-epoch"),
+        DisplayMode::Countdown => fl!("mode-countdown"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn configured_zone_is_applied_to_the_instant() {
+        let instant = Utc.with_ymd_and_hms(2024, 3, 14, 12, 0, 0).unwrap();
+        let plus_two = FixedOffset::east_opt(2 * 3600).unwrap();
+        let mut config = Config::default();
+        config.timezone = "Etc/GMT-2".to_string();
+        config.output_timezones.insert("DP-1".to_string(), "Etc/GMT+5".to_string());
+        let time = in_configured_zone(&config, "eDP-1", instant);
+        assert_eq!(time, instant);
+        assert_eq!(time.offset(), &plus_two);
+        assert_eq!(time.hour(), 14);
+        // A display with a zone of its own gets that one instead
+        let time = in_configured_zone(&config, "DP-1", instant);
+        assert_eq!(time.offset(), &FixedOffset::west_opt(5 * 3600).unwrap());
+        // Without a zone set, it is the system's
+        assert_eq!(in_configured_zone(&Config::default(), "eDP-1", instant), instant.with_timezone(&Local).fixed_offset());
+    }
+
+    #[test]
+    fn time_is_spoken_in_words() {
+        let time = |hour, minute| NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+        // Fluent wraps arguments in directional isolates, which a screen reader doesn't read out
+        let spoken = |hour, minute, twelve_hour| {
+            spoken_time(time(hour, minute), twelve_hour).replace(['\u{2068}', '\u{2069}'], "")
+        };
+        assert_eq!(spoken(15, 42, true), "three forty-two PM");
+        assert_eq!(spoken(15, 42, false), "fifteen forty-two");
+        assert_eq!(spoken(9, 5, true), "nine oh five AM");
+        assert_eq!(spoken(0, 30, true), "twelve thirty AM");
+        assert_eq!(spoken(12, 0, true), "twelve o'clock PM");
+        assert_eq!(spoken(7, 0, false), "seven hundred");
+        assert_eq!(spoken(23, 59, false), "twenty-three fifty-nine");
+    }
+
+    #[test]
+    fn the_app_reads_the_clock_in_the_configured_zone() {
+        let mut app = AppModel::default();
+        app.config.timezone = "Etc/GMT-2".to_string();
+        app.refresh_time();
+        assert_eq!(app.current_time.offset().local_minus_utc(), 2 * 3600);
+        assert_eq!(app.now_in_configured_zone().offset(), app.current_time.offset());
+    }
+
+    #[test]
+    fn ticks_are_not_jumps() {
+        assert!(!is_clock_jump(1000, 1000));
+        assert!(!is_clock_jump(1015, 1000));
+        assert!(!is_clock_jump(100, 100));
+        assert!(!is_clock_jump(60_000, 60_000));
+    }
+
+    #[test]
+    fn early_ticks_are_not_jumps() {
+        // The tick right after starting, when the time was only just read
+        assert!(!is_clock_jump(0, 1000));
+        assert!(!is_clock_jump(0, 60_000));
+        // The first tick on a minute boundary, half a minute after starting
+        assert!(!is_clock_jump(30_000, 60_000));
+    }
+
+    #[test]
+    fn suspends_and_clock_changes_are_jumps() {
+        // Woken up the next morning
+        assert!(is_clock_jump(8 * 3600 * 1000, 1000));
+        assert!(is_clock_jump(8 * 3600 * 1000, 60_000));
+        // A few seconds lost to a short suspend
+        assert!(is_clock_jump(5000, 1000));
+        // The clock set back an hour
+        assert!(is_clock_jump(-3600 * 1000, 1000));
+    }
+
+    #[test]
+    fn popup_stays_closed_without_a_main_window() {
+        // A fresh core has no surface yet, like while the panel is still starting up
+        let mut app = AppModel::default();
+        assert!(app.core.main_window_id().is_none());
+        let _ = app.toggle_popup(PopupKind::Settings);
+        assert!(app.popup.is_none());
+    }
+}
@@ -0,0 +1,1970 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The binary clock widget, which can be dropped into any COSMIC application's view as a canvas.
+
+use crate::config::{BitOrder, CellShape, ClockAlignment, DisplayMode, InactiveStyle, MeridiemStyle, Orientation};
+use chrono::{DateTime, Datelike, FixedOffset, Timelike, Weekday};
+use cosmic::iced::mouse;
+use cosmic::iced::widget::canvas;
+use cosmic::iced::{Color, Point, Radians, Rectangle, Renderer, Size, Vector};
+use std::cell::Cell;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::time::Duration;
+
+// Every column is as tall as its values need; this only sizes a grid with no columns to go by
+const DEFAULT_ROWS: u8 = 4;
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+// The largest minute or second
+const MAX_MINUTE: u32 = 59;
+const MAX_DAY: u32 = 31;
+// The largest number of hours the stopwatch counts to before it stays put
+const MAX_STOPWATCH_HOUR: u64 = 99;
+// The largest number of minutes a countdown can start from
+const MAX_COUNTDOWN_MINUTE: u64 = 99;
+// The share of the height left empty around the circles by default, so they don't touch the panel edges
+pub const PADDING_RATIO: f32 = 0.25;
+// How much of the bounds the padding above and below the columns can take up
+pub const MAX_PADDING: f32 = 0.6;
+// Widest padding at either end of the row of columns, in logical pixels
+pub const MAX_SIDE_PADDING: f32 = 32.0;
+// Extra space between the hours, minutes and seconds in a single row of bits, in radii
+const COMPACT_GROUP_GAP: f32 = 1.0;
+// Widest extra space between the hours, minutes and seconds, in radii
+pub const MAX_GROUP_GAP: f32 = 2.0;
+// Share of the height the weekday strip below the clock takes up
+const WEEKDAY_STRIP_RATIO: f32 = 0.2;
+// Gap between the circles of the weekday strip, in radii
+const WEEKDAY_SPACING: f32 = 0.5;
+// Share of the height the day progress bar at the very bottom takes up, margins included
+const DAY_PROGRESS_RATIO: f32 = 0.1;
+// How far the size of the circles can be scaled down or up
+pub const MIN_SCALE: f32 = 0.5;
+pub const MAX_SCALE: f32 = 2.0;
+// Below this radius, in pixels, circles can't be told apart and aren't worth drawing
+const MIN_RADIUS: f32 = 0.5;
+// Below this size, in pixels, place value labels are too small to read and are left out
+const MIN_PLACE_VALUE_TEXT: f32 = 5.0;
+// How far a touchpad has to scroll, in pixels, to count as one notch of a mouse wheel
+const PIXELS_PER_SCROLL_NOTCH: f32 = 50.0;
+// How strongly the place value hues tint lit and unlit circles; enough to tell rows apart, not to recolor them
+const ACTIVE_TINT: f32 = 0.3;
+const INACTIVE_TINT: f32 = 0.2;
+// How bright lit circles still are at the lowest brightness, so the clock stays readable
+const MIN_BRIGHTNESS: f32 = 0.25;
+// How far the hours and seconds are turned away from the minutes' hue when fields get their own colors
+const FIELD_HUE_SHIFT_DEGREES: f32 = 30.0;
+// How far the most significant bits are turned away from the least significant ones' hue in a gradient
+const GRADIENT_HUE_SHIFT_DEGREES: f32 = 90.0;
+// One hue per place value, from the ones upwards
+const PLACE_VALUE_HUES: [Color; 6] = [
+    Color::from_rgb(0.9, 0.3, 0.3),
+    Color::from_rgb(0.9, 0.7, 0.2),
+    Color::from_rgb(0.3, 0.8, 0.4),
+    Color::from_rgb(0.3, 0.6, 0.9),
+    Color::from_rgb(0.6, 0.4, 0.9),
+    Color::from_rgb(0.9, 0.4, 0.8),
+];
+
+/// A binary clock, drawn on a [`canvas::Canvas`].
+///
+/// Start from [`BinaryClock::new`] with the time to show and adjust it with the builder methods:
+///
+/// ```ignore
+/// let clock = BinaryClock::new(Local::now().fixed_offset())
+///     .mode(DisplayMode::BinaryHMS)
+///     .show_seconds(false)
+///     .on_press(Message::ClockClicked);
+/// canvas::Canvas::new(clock)
+/// ```
+#[derive(Debug, Clone)]
+pub struct BinaryClock<Message> {
+    mode: DisplayMode,
+    orientation: Orientation,
+    alignment: ClockAlignment,
+    cell_shape: CellShape,
+    /// Gap between neighbouring cells, as a fraction of their radius.
+    cell_spacing: f32,
+    /// Extra gap between the hours, minutes and seconds, in radii.
+    group_gap: f32,
+    /// How much larger or smaller than fits the panel the circles are drawn.
+    scale: f32,
+    /// Share of the height left empty above and below the columns, half on either side.
+    padding: f32,
+    /// Room left empty before the first column and after the last, in pixels.
+    side_padding: f32,
+    inactive_style: InactiveStyle,
+    bit_order: BitOrder,
+    current_time: DateTime<FixedOffset>,
+    /// The time on the stopwatch, shown instead of `current_time` in stopwatch mode.
+    elapsed: Duration,
+    /// The time left on the countdown, shown instead of `current_time` in countdown mode.
+    remaining: Duration,
+    /// How many low bits of the Unix timestamp to show in epoch mode.
+    epoch_bits: u8,
+    twelve_hour: bool,
+    meridiem_style: MeridiemStyle,
+    show_seconds: bool,
+    /// Draw the seconds of the time of day as a ring filling up over the minute, in place of their columns.
+    seconds_as_ring: bool,
+    /// Add a column for tenths of a second after the seconds.
+    show_deciseconds: bool,
+    /// Lay the columns out the other way around, with the seconds first and the hours last.
+    mirror: bool,
+    /// Draw thin lines between the hour, minute and second columns.
+    show_labels: bool,
+    /// Draw a colon between the hours and minutes, shown on even seconds and hidden on odd ones.
+    blink_separator: bool,
+    /// Brightness of the heartbeat on the lowest bit, from 0 (none) to 1 (just ticked).
+    heartbeat: f32,
+    /// Brightness of the flash over the whole clock, from 0 (none) to 1 (the minute just started).
+    minute_pulse: f32,
+    /// The time shown before the last tick, while bits are still fading from it.
+    previous_time: Option<DateTime<FixedOffset>>,
+    /// How far bits have faded from `previous_time`, from 0 (not at all) to 1 (done).
+    fade: f32,
+    /// Fixed color for lit circles, instead of the one derived from the theme.
+    active_color: Option<Color>,
+    /// Fixed color for unlit circles, instead of the one derived from the theme.
+    inactive_color: Option<Color>,
+    /// Tint each row with its own hue, so the place value of every bit stands out.
+    place_value_tint: bool,
+    /// Write the place value of every bit faintly inside its circle.
+    show_place_values: bool,
+    /// Draw in pure black and white with heavier outlines, whatever the theme or colors are.
+    high_contrast: bool,
+    /// Swap the lit and unlit colors, so lit bits are the darker ones.
+    invert: bool,
+    /// Outline lit cells in a contrasting color.
+    cell_border: bool,
+    /// Highlight the column under the pointer and write its value over it.
+    column_hints: bool,
+    /// The column to highlight with its value written over it, whatever the pointer does.
+    highlighted_column: Option<usize>,
+    /// How bright lit circles are, from 0 (dimmed, but still visible) to 1 (full color).
+    brightness: f32,
+    /// Leave out the tens of hours column while it is zero.
+    blank_leading_zero: bool,
+    /// Draw a rounded panel behind the circles.
+    show_background: bool,
+    /// Write the value of each column below it.
+    show_digit_labels: bool,
+    /// Show the day of the month in a single column, instead of the time.
+    day_of_month: bool,
+    /// Show only the hours and minutes in binary, a column each, whatever the mode.
+    compact: bool,
+    /// Draw a strip of seven circles below the clock, Monday first, with today's lit.
+    show_weekday: bool,
+    /// What to write in the circles of the weekday strip, Monday first, if anything.
+    weekday_labels: Option<[String; 7]>,
+    /// The day the weekday strip starts with.
+    first_weekday: Weekday,
+    /// Draw a thin bar along the bottom, filled as far as the day has passed.
+    show_day_progress: bool,
+    /// Separate lit colors for the hours, minutes and seconds, or `None` for one color throughout.
+    /// Fields without a color of their own get a variation on the active color.
+    field_colors: Option<[Option<Color>; 3]>,
+    /// Lit colors for the least and most significant bits, blended between for the bits in
+    /// between, or `None` for no gradient. Ends without a color of their own get the active
+    /// color and a variation on it.
+    gradient: Option<[Option<Color>; 2]>,
+    /// Emitted when the clock is clicked with the left button.
+    on_press: Option<Message>,
+    /// Emitted when the clock is clicked with the right button.
+    on_right_press: Option<Message>,
+    /// Emitted for every notch the clock is scrolled down and up over, in that order.
+    on_scroll: Option<(Message, Message)>,
+}
+
+/// The colors circles are filled with.
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    pub active: Color,
+    pub inactive: Color,
+    /// Lines drawn between the fields.
+    pub separator: Color,
+    /// Panel drawn behind the circles.
+    pub background: Color,
+    /// Background shown while the pointer is over the clock.
+    pub hover: Color,
+    /// Glow laid over the heartbeat circle.
+    pub pulse: Color,
+    /// Digits written below the columns.
+    pub text: Color,
+}
+
+/// What the pointer is doing with the clock, and what was drawn last.
+#[derive(Default)]
+pub struct ClockState {
+    hovered: bool,
+    /// The column under the pointer, while column hints are on.
+    hovered_column: Option<usize>,
+    /// Everything but the animations, as of the last change to what the clock shows.
+    cache: canvas::Cache,
+    /// Fingerprint of what `cache` holds, see [`BinaryClock::cache_key`].
+    cached: Cell<Option<u64>>,
+    /// Scrolling over the clock that hasn't added up to a whole notch yet, in notches.
+    scrolled: f32,
+}
+
+/// The part of the time a column belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Field {
+    Hour,
+    Minute,
+    Second,
+    Meridiem,
+    /// A digit of the fraction of the day that has passed.
+    DayFraction,
+    /// The day of the month.
+    Day,
+    /// Tenths of a second.
+    Decisecond,
+    /// Bits of the Unix timestamp.
+    Epoch,
+}
+
+/// A single column of circles, showing `value` in binary.
+#[derive(Debug, Clone, Copy, Hash)]
+struct Column {
+    field: Field,
+    value: u32,
+    /// How many circles tall this column is. Shorter columns sit on the same baseline as the rest.
+    rows: u8,
+}
+
+impl Column {
+    /// A column of `rows` bits showing `value`, which has to fit in them.
+    fn new(field: Field, value: u32, rows: u8) -> Self {
+        debug_assert!(
+            rows > 0 && u32::from(rows) >= bits_needed(value).into(),
+            "{field:?} column of {rows} rows can't hold {value}"
+        );
+        Self { field, value, rows }
+    }
+
+    /// A column just tall enough to show any value up to `max`, and never taller.
+    fn up_to(field: Field, value: u32, max: u32) -> Self {
+        // Even a column that can only ever show zero gets a circle, so it doesn't vanish
+        Self::new(field, value, bits_needed(max).max(1))
+    }
+
+    /// Splits `value` into one single-circle column per bit, most significant first,
+    /// so the bits are laid out in a row.
+    fn bits(field: Field, value: u32, bits: u8) -> impl Iterator<Item = Self> {
+        (0..bits).rev().map(move |bit| Self::new(field, u32::from(is_set(value, bit)), 1))
+    }
+
+    /// A column for the tens digit of `value` and one for the ones digit, each just tall enough
+    /// for the digits any value up to `max` can have.
+    ///
+    /// Tens digits never need all four bits, so their columns are shorter.
+    fn digits(field: Field, value: u32, max: u32) -> [Self; 2] {
+        let (tens, ones) = digits(value);
+        [Self::up_to(field, tens, digits(max).0), Self::up_to(field, ones, 9)]
+    }
+
+    /// Whether the circle in `circle_row` is lit, where row 0 holds the least significant bit.
+    /// Rows above the top of the column are never lit.
+    fn is_lit(&self, circle_row: u8) -> bool {
+        circle_row < self.rows && is_set(self.value, circle_row)
+    }
+}
+
+/// Splits a value below 100 into its tens and ones digits.
+fn digits(value: u32) -> (u32, u32) {
+    (value / 10, value % 10)
+}
+
+/// Whether `bit` is set in `value`, counting from the least significant bit.
+fn is_set(value: u32, bit: u8) -> bool {
+    (value >> bit) & 1 == 1
+}
+
+/// How much room a [`Grid`] leaves between and around its circles.
+#[derive(Debug, Clone, Copy)]
+struct Spacing {
+    /// Between neighbouring circles, in radii.
+    cells: f32,
+    /// Extra between the last column of a field and the first of the next, in radii.
+    groups: f32,
+    /// Share of the bounds along the columns left empty, half at either end.
+    padding: f32,
+    /// Left empty at either end of the row of columns, in pixels.
+    side: f32,
+}
+
+/// Where the circles go: columns of `rows` circles each, centered along the bounds and aligned
+/// across them.
+///
+/// Columns are laid out "across" the grid and the bits of a column run "along" it. Horizontally
+/// that is left to right and top to bottom respectively, vertically it is the other way around.
+#[derive(Debug, Clone, Copy)]
+struct Grid {
+    orientation: Orientation,
+    rows: u8,
+    radius: f32,
+    /// Distance between the centers of neighbouring circles.
+    pitch: f32,
+    /// Distance from the edge of the bounds to the first column.
+    across_start: f32,
+    /// Distance from the edge of the bounds to the first row.
+    along_start: f32,
+    /// Distance from the first row to the far edge of the last one.
+    along_length: f32,
+    /// Size of the bounds along the columns.
+    along: f32,
+    /// Extra distance in front of the first column of every field but the first.
+    group_gap: f32,
+    /// One bit per column, set for the columns that start a new field after another one.
+    group_starts: u64,
+}
+
+impl Grid {
+    /// Fits `columns` of up to `rows` circles into the bounds, leaving room between and around
+    /// them as set out in `spacing`.
+    ///
+    /// The radius is the largest that fits both ways, so on short, wide panels the circles are sized
+    /// by the height and centered across, and on tall, narrow ones they shrink to fit the width.
+    ///
+    /// `scale` grows or shrinks the circles from there, eating into the padding if need be, but
+    /// never so far that they no longer fit the bounds. Room left over across is given to either
+    /// side as `alignment` says.
+    fn new(
+        orientation: Orientation,
+        alignment: ClockAlignment,
+        columns: &[Column],
+        rows: u8,
+        spacing: Spacing,
+        scale: f32,
+        bounds: Rectangle,
+    ) -> Self {
+        let group_starts = Self::group_starts(columns);
+        let (across, along) = match orientation {
+            Orientation::Horizontal => (bounds.width, bounds.height),
+            Orientation::Vertical => (bounds.height, bounds.width),
+        };
+        let (span_across, span_along) = Self::spans(columns, rows, spacing);
+        // This is the amount of space we have available, subtract the padding, which scales with the panel
+        let available_along = (along * (1.0 - spacing.padding)).max(0.0);
+        // The radius will be the the available space divided by the number of radii it has to hold,
+        // unless that would make the columns too wide to fit next to each other
+        let fitting_across = (across - spacing.side * 2.0).max(0.0) / span_across;
+        let radius = (available_along / span_along).min(fitting_across);
+        let radius = (radius * scale).min(along.max(0.0) / span_along).min(fitting_across);
+        let across_start = match alignment {
+            ClockAlignment::Left => spacing.side,
+            ClockAlignment::Center => (across - radius * span_across) / 2.0,
+            ClockAlignment::Right => across - radius * span_across - spacing.side,
+        };
+        Self {
+            orientation,
+            rows,
+            radius,
+            pitch: radius * (2.0 + spacing.cells),
+            across_start,
+            along_start: (along - radius * span_along) / 2.0,
+            along_length: radius * span_along,
+            along,
+            group_gap: radius * spacing.groups,
+            group_starts,
+        }
+    }
+
+    /// One bit per column, set for the columns that start a new field after another one.
+    fn group_starts(columns: &[Column]) -> u64 {
+        columns
+            .windows(2)
+            .enumerate()
+            .filter(|(_, pair)| pair[0].field != pair[1].field)
+            .fold(0u64, |starts, (index, _)| starts | 1 << (index + 1).min(63))
+    }
+
+    /// How many radii `columns` of up to `rows` circles take up across and along, gaps included.
+    fn spans(columns: &[Column], rows: u8, spacing: Spacing) -> (f32, f32) {
+        // How many radii a line of `n` circles takes up
+        let span = |n: usize| (n * 2) as f32 + spacing.cells * n.saturating_sub(1) as f32;
+        // Columns also make room for the gaps between fields
+        let span_across = span(columns.len()) + spacing.groups * Self::group_starts(columns).count_ones() as f32;
+        (span_across, span(rows.into()))
+    }
+
+    /// Maps a distance across and along the grid to a point within the bounds.
+    fn point(&self, across: f32, along: f32) -> Point {
+        match self.orientation {
+            Orientation::Horizontal => Point::new(across, along),
+            Orientation::Vertical => Point::new(along, across),
+        }
+    }
+
+    /// The distance across the grid at which the column at `index` starts.
+    fn column_start(&self, index: usize) -> f32 {
+        // Every field that started up to and including this column pushes it along
+        let groups = (self.group_starts & (u64::MAX >> (63 - index.min(63)))).count_ones();
+        self.across_start + self.pitch * index as f32 + self.group_gap * groups as f32
+    }
+
+    /// The index of the column under `point`, out of `count` columns, if there is one.
+    fn column_at(&self, point: Point, count: usize) -> Option<usize> {
+        let (across, along) = match self.orientation {
+            Orientation::Horizontal => (point.x, point.y),
+            Orientation::Vertical => (point.y, point.x),
+        };
+        if along < self.along_start || along > self.along_start + self.along_length {
+            return None;
+        }
+        (0..count).find(|&index| {
+            let start = self.column_start(index);
+            (start..=start + self.radius * 2.0).contains(&across)
+        })
+    }
+
+    /// The distance across the grid halfway between the column at `index` and the one before it.
+    fn gap_center(&self, index: usize) -> f32 {
+        let mut gap = self.pitch - self.radius * 2.0;
+        if self.group_starts & (1 << index.min(63)) != 0 {
+            gap += self.group_gap;
+        }
+        self.column_start(index) - gap / 2.0
+    }
+
+    /// The center of the circle in `slot` of the column at `index`, counting slots from the top
+    /// (or from the left when vertical).
+    fn center(&self, index: usize, slot: u8) -> Point {
+        // Offset by the radius, so that the first circle just touches the boundary rather than be on it
+        self.point(
+            self.column_start(index) + self.radius,
+            self.along_start + self.pitch * slot as f32 + self.radius,
+        )
+    }
+}
+
+/// Blends the theme's colors towards the hue of the place value of `circle_row`.
+fn tint(palette: Palette, circle_row: u8) -> Palette {
+    let hue = PLACE_VALUE_HUES[usize::from(circle_row) % PLACE_VALUE_HUES.len()];
+    Palette {
+        active: mix(palette.active, hue, ACTIVE_TINT),
+        inactive: mix(palette.inactive, hue, INACTIVE_TINT),
+        ..palette
+    }
+}
+
+/// Moves `amount` of the way from `from` to `to`, keeping the alpha of `from`.
+fn mix(from: Color, to: Color, amount: f32) -> Color {
+    Color {
+        r: from.r + (to.r - from.r) * amount,
+        g: from.g + (to.g - from.g) * amount,
+        b: from.b + (to.b - from.b) * amount,
+        a: from.a,
+    }
+}
+
+/// Turns the hue of `color` by `degrees`, keeping its brightness about the same.
+fn shift_hue(color: Color, degrees: f32) -> Color {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let Color { r, g, b, a } = color;
+    let channel = |x: f32| x.clamp(0.0, 1.0);
+    Color {
+        r: channel(
+            r * (0.213 + cos * 0.787 - sin * 0.213)
+                + g * (0.715 - cos * 0.715 - sin * 0.715)
+                + b * (0.072 - cos * 0.072 + sin * 0.928),
+        ),
+        g: channel(
+            r * (0.213 - cos * 0.213 + sin * 0.143)
+                + g * (0.715 + cos * 0.285 + sin * 0.140)
+                + b * (0.072 - cos * 0.072 - sin * 0.283),
+        ),
+        b: channel(
+            r * (0.213 - cos * 0.213 - sin * 0.787)
+                + g * (0.715 - cos * 0.715 + sin * 0.715)
+                + b * (0.072 + cos * 0.928 + sin * 0.072),
+        ),
+        a,
+    }
+}
+
+/// Roughly how wide `content` comes out in text `size` high. Digits are about 0.6 em wide and
+/// letters about as much, which is close enough to center them by.
+fn text_width(content: &str, size: f32) -> f32 {
+    size * 0.6 * content.chars().count() as f32
+}
+
+/// Writes `content` in text `size` high, centered on `center`.
+fn centered_text(frame: &mut canvas::Frame, center: Point, content: String, size: f32, color: Color) {
+    frame.fill_text(canvas::Text {
+        position: center - Vector::new(text_width(&content, size) / 2.0, size / 2.0),
+        content,
+        color,
+        size: size.into(),
+        ..canvas::Text::default()
+    });
+}
+
+/// How many bits it takes to write `max` in binary.
+fn bits_needed(max: u32) -> u8 {
+    (u32::BITS - max.leading_zeros()) as u8
+}
+
+impl<Message> BinaryClock<Message> {
+    /// A clock showing `current_time` in the default style, which doesn't react to clicks.
+    pub fn new(current_time: DateTime<FixedOffset>) -> Self {
+        Self {
+            mode: DisplayMode::default(),
+            orientation: Orientation::default(),
+            alignment: ClockAlignment::default(),
+            cell_shape: CellShape::default(),
+            cell_spacing: 0.0,
+            group_gap: 0.0,
+            scale: 1.0,
+            padding: PADDING_RATIO,
+            side_padding: 0.0,
+            inactive_style: InactiveStyle::default(),
+            bit_order: BitOrder::default(),
+            current_time,
+            elapsed: Duration::ZERO,
+            remaining: Duration::ZERO,
+            epoch_bits: 24,
+            twelve_hour: false,
+            meridiem_style: MeridiemStyle::default(),
+            show_seconds: true,
+            seconds_as_ring: false,
+            show_deciseconds: false,
+            mirror: false,
+            show_labels: false,
+            blink_separator: false,
+            heartbeat: 0.0,
+            minute_pulse: 0.0,
+            previous_time: None,
+            fade: 1.0,
+            active_color: None,
+            inactive_color: None,
+            place_value_tint: false,
+            show_place_values: false,
+            high_contrast: false,
+            invert: false,
+            cell_border: false,
+            column_hints: false,
+            highlighted_column: None,
+            brightness: 1.0,
+            blank_leading_zero: false,
+            show_background: false,
+            show_digit_labels: false,
+            day_of_month: false,
+            compact: false,
+            show_weekday: false,
+            weekday_labels: None,
+            first_weekday: Weekday::Mon,
+            show_day_progress: false,
+            field_colors: None,
+            gradient: None,
+            on_press: None,
+            on_right_press: None,
+            on_scroll: None,
+        }
+    }
+
+    /// Sets how the time is encoded into columns.
+    pub fn mode(mut self, mode: DisplayMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets which way the columns and bits are laid out.
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Sets where the columns sit when the clock is given more room across than they fill, like
+    /// in a wide window.
+    pub fn alignment(mut self, alignment: ClockAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Sets the shape each bit is drawn as.
+    pub fn cell_shape(mut self, cell_shape: CellShape) -> Self {
+        self.cell_shape = cell_shape;
+        self
+    }
+
+    /// Sets the gap between neighbouring cells, from 0 (touching) to 1 (a full radius apart).
+    pub fn cell_spacing(mut self, cell_spacing: f32) -> Self {
+        self.cell_spacing = cell_spacing.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the extra gap between the hours, minutes and seconds, from 0 to [`MAX_GROUP_GAP`]
+    /// radii. The two digits of a field stay as close as any other cells.
+    pub fn group_gap(mut self, group_gap: f32) -> Self {
+        self.group_gap = group_gap.clamp(0.0, MAX_GROUP_GAP);
+        self
+    }
+
+    /// Draws the circles larger or smaller, from half (0.5) to twice (2.0) the size that fits
+    /// the bounds with some padding. They are never drawn larger than the bounds.
+    pub fn scale(mut self, scale: f32) -> Self {
+        self.scale = scale.clamp(MIN_SCALE, MAX_SCALE);
+        self
+    }
+
+    /// Leaves `padding` of the height empty above and below the columns, from 0 (circles touch
+    /// the edges) up to 0.6. Vertical clocks leave it to the left and right instead.
+    pub fn padding(mut self, padding: f32) -> Self {
+        self.padding = padding.clamp(0.0, MAX_PADDING);
+        self
+    }
+
+    /// Leaves `padding` pixels empty before the first column and after the last.
+    pub fn side_padding(mut self, padding: f32) -> Self {
+        self.side_padding = padding.clamp(0.0, MAX_SIDE_PADDING);
+        self
+    }
+
+    /// Sets how bits that are off are drawn.
+    pub fn inactive_style(mut self, inactive_style: InactiveStyle) -> Self {
+        self.inactive_style = inactive_style;
+        self
+    }
+
+    /// Sets which end of a column the most significant bit is drawn at.
+    pub fn bit_order(mut self, bit_order: BitOrder) -> Self {
+        self.bit_order = bit_order;
+        self
+    }
+
+    /// Shows the time in another time zone.
+    pub fn timezone(mut self, offset: FixedOffset) -> Self {
+        self.current_time = self.current_time.with_timezone(&offset);
+        self
+    }
+
+    /// Sets the time on the stopwatch, which is shown instead of the time of day in stopwatch mode.
+    pub fn elapsed(mut self, elapsed: Duration) -> Self {
+        self.elapsed = elapsed;
+        self
+    }
+
+    /// Sets the time left on the countdown, which is shown instead of the time of day in countdown mode.
+    pub fn remaining(mut self, remaining: Duration) -> Self {
+        self.remaining = remaining;
+        self
+    }
+
+    /// Sets how many low bits of the Unix timestamp epoch mode shows, up to 32. Fewer bits fit a
+    /// smaller panel, but roll over sooner.
+    pub fn epoch_bits(mut self, bits: u8) -> Self {
+        self.epoch_bits = bits.clamp(1, 32);
+        self
+    }
+
+    /// Shows hours as 1-12 with an AM/PM indicator instead of 0-23.
+    pub fn twelve_hour(mut self, twelve_hour: bool) -> Self {
+        self.twelve_hour = twelve_hour;
+        self
+    }
+
+    /// Sets how the AM/PM indicator is drawn in twelve hour mode.
+    pub fn meridiem_style(mut self, meridiem_style: MeridiemStyle) -> Self {
+        self.meridiem_style = meridiem_style;
+        self
+    }
+
+    /// Draws the seconds columns, rather than only hours and minutes.
+    pub fn show_seconds(mut self, show_seconds: bool) -> Self {
+        self.show_seconds = show_seconds;
+        self
+    }
+
+    /// Draws the seconds as a ring that fills up clockwise over the minute, in the room their
+    /// columns would take, for a bit of an analog feel. The stopwatch and countdown keep their
+    /// columns.
+    pub fn seconds_as_ring(mut self, seconds_as_ring: bool) -> Self {
+        self.seconds_as_ring = seconds_as_ring;
+        self
+    }
+
+    /// Adds a column for tenths of a second after the seconds, wherever they are shown.
+    ///
+    /// The clock has to be redrawn ten times a second for it to keep up.
+    pub fn show_deciseconds(mut self, show_deciseconds: bool) -> Self {
+        self.show_deciseconds = show_deciseconds;
+        self
+    }
+
+    /// Lays the columns out the other way around, seconds first and hours last, for right to left readers.
+    pub fn mirror(mut self, mirror: bool) -> Self {
+        self.mirror = mirror;
+        self
+    }
+
+    /// Separates the hour, minute and second columns with thin lines.
+    pub fn show_labels(mut self, show_labels: bool) -> Self {
+        self.show_labels = show_labels;
+        self
+    }
+
+    /// Draws a colon between the hours and the minutes that blinks every second, like the one
+    /// on a digital clock. Needs ticks every second to blink.
+    pub fn blink_separator(mut self, blink_separator: bool) -> Self {
+        self.blink_separator = blink_separator;
+        self
+    }
+
+    /// Sets the brightness of the glow on the lowest bit, from 0 (none) to 1 (just ticked).
+    pub fn heartbeat(mut self, heartbeat: f32) -> Self {
+        self.heartbeat = heartbeat;
+        self
+    }
+
+    /// Sets the brightness of a flash over the whole clock, from 0 (none) to 1 (just started).
+    pub fn minute_pulse(mut self, minute_pulse: f32) -> Self {
+        self.minute_pulse = minute_pulse;
+        self
+    }
+
+    /// Crossfades bits that changed since `previous_time`, `fade` of the way from 0 to 1.
+    pub fn fade_from(mut self, previous_time: Option<DateTime<FixedOffset>>, fade: f32) -> Self {
+        self.previous_time = previous_time;
+        self.fade = fade.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets a fixed color for lit circles, or uses the theme's accent color when `None`.
+    pub fn active_color(mut self, color: Option<Color>) -> Self {
+        self.active_color = color;
+        self
+    }
+
+    /// Sets a fixed color for unlit circles, or uses a muted theme color when `None`.
+    pub fn inactive_color(mut self, color: Option<Color>) -> Self {
+        self.inactive_color = color;
+        self
+    }
+
+    /// Tints each row with its own hue, so the ones, twos, fours and eights are easy to tell apart.
+    pub fn place_value_tint(mut self, place_value_tint: bool) -> Self {
+        self.place_value_tint = place_value_tint;
+        self
+    }
+
+    /// Writes the place value of every bit (1, 2, 4, 8 and so on) faintly inside its circle, so the
+    /// value of a column can be added up without knowing binary by heart.
+    pub fn show_place_values(mut self, show_place_values: bool) -> Self {
+        self.show_place_values = show_place_values;
+        self
+    }
+
+    /// Draws pure white lit and pure black unlit circles with a heavy outline, in place of the
+    /// theme and any chosen colors, for people who can't make out the subtler ones.
+    pub fn high_contrast(mut self, high_contrast: bool) -> Self {
+        self.high_contrast = high_contrast;
+        self
+    }
+
+    /// Fills lit bits with the unlit color and the other way around, whichever colors are in use,
+    /// for bright backgrounds where dark bits read as the ones that are on.
+    pub fn invert(mut self, invert: bool) -> Self {
+        self.invert = invert;
+        self
+    }
+
+    /// Outlines lit cells in the theme's text color, so they stand out against a background of
+    /// about the same brightness.
+    pub fn cell_border(mut self, cell_border: bool) -> Self {
+        self.cell_border = cell_border;
+        self
+    }
+
+    /// Highlights the column under the pointer and writes its value in decimal over it, to help
+    /// learning to read the clock. Best left off where the clock is small, like in the panel.
+    pub fn column_hints(mut self, column_hints: bool) -> Self {
+        self.column_hints = column_hints;
+        self
+    }
+
+    /// Highlights the column at `index` and writes its value over it, like a column hint that
+    /// stays put. A column under the pointer takes its place while column hints are on.
+    pub fn highlight_column(mut self, index: Option<usize>) -> Self {
+        self.highlighted_column = index;
+        self
+    }
+
+    /// Dims lit circles, from 0 (as dim as still readable) to 1 (full color).
+    pub fn brightness(mut self, brightness: f32) -> Self {
+        self.brightness = brightness.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Leaves out the tens of hours column while it is zero, rather than showing it all off.
+    pub fn blank_leading_zero(mut self, blank_leading_zero: bool) -> Self {
+        self.blank_leading_zero = blank_leading_zero;
+        self
+    }
+
+    /// Draws a rounded panel in a theme container color behind the circles, to frame the clock.
+    pub fn show_background(mut self, show_background: bool) -> Self {
+        self.show_background = show_background;
+        self
+    }
+
+    /// Writes the value of each column below it in decimal, to help learning to read the clock.
+    pub fn show_digit_labels(mut self, show_digit_labels: bool) -> Self {
+        self.show_digit_labels = show_digit_labels;
+        self
+    }
+
+    /// Shows the day of the month, from 1 to 31, as a single column of five bits instead of the time.
+    pub fn day_of_month(mut self, day_of_month: bool) -> Self {
+        self.day_of_month = day_of_month;
+        self
+    }
+
+    /// Shows just the hours and minutes, in binary, a column each, to glance at in a crowded panel.
+    /// Leaves out the seconds and everything drawn around the columns, whatever else is set, so
+    /// it goes after the other settings.
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        if compact {
+            self.show_weekday = false;
+            self.show_day_progress = false;
+        }
+        self
+    }
+
+    /// Draws a strip of seven circles below the clock, one for every day of the week starting
+    /// with the one set with [`BinaryClock::first_weekday`], with today's lit.
+    pub fn show_weekday(mut self, show_weekday: bool) -> Self {
+        self.show_weekday = show_weekday;
+        self
+    }
+
+    /// Writes `labels` in the circles of the weekday strip, Monday first, so it is clear which
+    /// end the week starts at. They should be short, a letter or two.
+    pub fn weekday_labels(mut self, labels: Option<[String; 7]>) -> Self {
+        self.weekday_labels = labels;
+        self
+    }
+
+    /// Sets the day the weekday strip starts with, Monday unless set otherwise.
+    pub fn first_weekday(mut self, first_weekday: Weekday) -> Self {
+        self.first_weekday = first_weekday;
+        self
+    }
+
+    /// Draws a thin bar along the bottom, empty at midnight and filling up as the day passes.
+    pub fn show_day_progress(mut self, show_day_progress: bool) -> Self {
+        self.show_day_progress = show_day_progress;
+        self
+    }
+
+    /// Gives the hours, minutes and seconds lit colors of their own, in that order. Fields left at
+    /// `None` get a slight variation on the hue of the active color. `None` as a whole uses one
+    /// color throughout.
+    pub fn field_colors(mut self, field_colors: Option<[Option<Color>; 3]>) -> Self {
+        self.field_colors = field_colors;
+        self
+    }
+
+    /// Blends the lit color of every column from the least significant bit to the most significant
+    /// one, so higher value bits stand out. Ends left at `None` get the active color and a turn of
+    /// its hue respectively. `None` as a whole lights every bit of a column alike.
+    pub fn gradient(mut self, gradient: Option<[Option<Color>; 2]>) -> Self {
+        self.gradient = gradient;
+        self
+    }
+
+    /// Sets the message emitted when the clock is clicked with the left button.
+    pub fn on_press(mut self, message: Message) -> Self {
+        self.on_press = Some(message);
+        self
+    }
+
+    /// Sets the message emitted when the clock is clicked with the right button.
+    pub fn on_right_press(mut self, message: Message) -> Self {
+        self.on_right_press = Some(message);
+        self
+    }
+
+    /// Sets the messages emitted for every notch the clock is scrolled over, `down` when scrolling
+    /// down and `up` when scrolling up.
+    pub fn on_scroll(mut self, down: Message, up: Message) -> Self {
+        self.on_scroll = Some((down, up));
+        self
+    }
+
+    /// Whether the circle in `row` of the column at `column` is lit, counting columns in the order
+    /// they are drawn and rows from the least significant bit. This is what `draw` lights up, worked
+    /// out without drawing anything.
+    ///
+    /// Circles outside of the clock are never lit.
+    pub fn bit_at(&self, column: u8, row: u8) -> bool {
+        self.columns().get(usize::from(column)).is_some_and(|column| column.is_lit(row))
+    }
+
+    /// How many columns the clock shows, counted as in [`BinaryClock::bit_at`].
+    pub fn column_count(&self) -> usize {
+        self.columns().len()
+    }
+
+    /// The value shown in the column at `column`, counted as in [`BinaryClock::bit_at`], and how
+    /// many bits tall it is. `None` past the last column.
+    pub fn column_value(&self, column: usize) -> Option<(u32, u8)> {
+        self.columns().get(column).map(|column| (column.value, column.rows))
+    }
+
+    /// The center of the circle for the bit counted as in [`BinaryClock::bit_at`], from the top left
+    /// corner of the clock drawn `size` big. `None` when there is no such circle, or no room to draw it.
+    ///
+    /// Along with `bit_at` this tells where to look in a rendering of the clock, such as an exported
+    /// image of a frozen time, and whether to expect the lit or the unlit color there.
+    pub fn bit_center(&self, column: u8, row: u8, size: Size) -> Option<Point> {
+        let columns = self.columns();
+        let grid = self.grid(&columns, Rectangle::with_size(size));
+        if grid.radius.is_nan() || grid.radius < MIN_RADIUS || row >= columns.get(usize::from(column))?.rows {
+            return None;
+        }
+        Some(grid.center(usize::from(column), self.slot(grid, row)))
+    }
+
+    /// The theme's accent for lit circles and a muted component tone for unlit ones,
+    /// unless the configuration overrides them.
+    pub fn palette(&self, theme: &cosmic::Theme) -> Palette {
+        if self.high_contrast {
+            return Palette {
+                active: self.dimmed(Color::WHITE),
+                inactive: Color::BLACK,
+                separator: Color::WHITE,
+                background: Color::BLACK,
+                hover: Color::from_rgb(0.25, 0.25, 0.25),
+                pulse: Color::WHITE,
+                text: Color::WHITE,
+            };
+        }
+        let cosmic = theme.cosmic();
+        Palette {
+            active: self.dimmed(self.active_color.unwrap_or_else(|| cosmic.accent_color().into())),
+            inactive: self.inactive_color.unwrap_or_else(|| cosmic.background.component.base.into()),
+            separator: Color {
+                a: 0.4,
+                ..cosmic.background.on.into()
+            },
+            background: cosmic.primary.base.into(),
+            hover: cosmic.background.component.hover.into(),
+            pulse: cosmic.background.on.into(),
+            text: cosmic.background.on.into(),
+        }
+    }
+
+    /// The lit colors of the hours, minutes and seconds, in that order.
+    pub fn field_palette_colors(&self, theme: &cosmic::Theme) -> [Color; 3] {
+        let palette = self.palette(theme);
+        [Field::Hour, Field::Minute, Field::Second].map(|field| self.field_palette(palette, field).active)
+    }
+
+    /// The palette for the columns of `field`, with its own lit color when fields are colored apart.
+    fn field_palette(&self, palette: Palette, field: Field) -> Palette {
+        let Some(colors) = self.field_colors.filter(|_| !self.high_contrast) else {
+            return palette;
+        };
+        let (color, shift) = match field {
+            Field::Hour => (colors[0], -FIELD_HUE_SHIFT_DEGREES),
+            Field::Minute => (colors[1], 0.0),
+            Field::Second | Field::Decisecond => (colors[2], FIELD_HUE_SHIFT_DEGREES),
+            Field::Meridiem | Field::DayFraction | Field::Day | Field::Epoch => return palette,
+        };
+        Palette {
+            active: color.map_or_else(|| shift_hue(palette.active, shift), |color| self.dimmed(color)),
+            ..palette
+        }
+    }
+
+    /// The lit colors of the least and most significant bits in a gradient, for showing next to
+    /// the settings. They are the active color and a variation on it when unset.
+    pub fn gradient_palette_colors(&self, theme: &cosmic::Theme) -> [Color; 2] {
+        self.gradient_ends(self.palette(theme))
+    }
+
+    /// The gradient's colors at the least and most significant bits, on top of `palette`.
+    fn gradient_ends(&self, palette: Palette) -> [Color; 2] {
+        let [low, high] = self.gradient.unwrap_or_default();
+        let low = low.map_or(palette.active, |color| self.dimmed(color));
+        let high = high.map_or_else(|| shift_hue(low, GRADIENT_HUE_SHIFT_DEGREES), |color| self.dimmed(color));
+        [low, high]
+    }
+
+    /// The palette for the bit in `circle_row` of a grid `rows` high, lit along the gradient if there is one.
+    fn gradient_palette(&self, palette: Palette, circle_row: u8, rows: u8) -> Palette {
+        if self.gradient.is_none() || self.high_contrast {
+            return palette;
+        }
+        let [low, high] = self.gradient_ends(palette);
+        // Measured against the grid rather than the column, so each place value has one color throughout
+        let factor = f32::from(circle_row) / f32::from(rows.saturating_sub(1).max(1));
+        Palette {
+            active: mix(low, high, factor),
+            ..palette
+        }
+    }
+
+    /// Scales `color` down to the configured brightness.
+    fn dimmed(&self, color: Color) -> Color {
+        let scale = MIN_BRIGHTNESS + (1.0 - MIN_BRIGHTNESS) * self.brightness;
+        Color {
+            r: color.r * scale,
+            g: color.g * scale,
+            b: color.b * scale,
+            a: color.a,
+        }
+    }
+
+    /// The outline of a single cell, centered on `center` and `radius` wide in each direction.
+    fn cell(&self, center: Point, radius: f32) -> canvas::Path {
+        // Squares would merge into one block where they touch, so pull them in a little
+        let half = radius * 0.9;
+        let top_left = center - Vector::new(half, half);
+        let size = Size::new(half * 2.0, half * 2.0);
+        match self.cell_shape {
+            CellShape::Circle => canvas::Path::circle(center, radius),
+            CellShape::Square => canvas::Path::rectangle(top_left, size),
+            CellShape::RoundedSquare => canvas::Path::rounded_rectangle(top_left, size, (half * 0.4).into()),
+        }
+    }
+
+    /// The width of outlines around cells of `radius`, heavier in high contrast.
+    fn stroke_width(&self, radius: f32) -> f32 {
+        let width = if self.high_contrast { radius / 4.0 } else { radius / 6.0 };
+        width.max(1.0)
+    }
+
+    fn circle(&self, frame: &mut canvas::Frame, position: Point, radius: f32, palette: Palette, active: bool) {
+        let circle = self.cell(position, radius);
+        if active {
+            frame.fill(&circle, palette.active);
+            if self.cell_border {
+                let border = canvas::Stroke::default()
+                    .with_width((radius / 8.0).max(1.0))
+                    .with_color(palette.text);
+                frame.stroke(&circle, border);
+            }
+            return;
+        }
+        // Black on a black background would vanish, so unlit cells get a white outline too
+        let outline = if self.high_contrast { palette.active } else { palette.inactive };
+        let stroke = canvas::Stroke::default()
+            .with_width(self.stroke_width(radius))
+            .with_color(outline);
+        match self.inactive_style {
+            InactiveStyle::Filled => {
+                frame.fill(&circle, palette.inactive);
+                if self.high_contrast {
+                    frame.stroke(&circle, stroke);
+                }
+            }
+            InactiveStyle::Outline => frame.stroke(&circle, stroke),
+            InactiveStyle::Hidden => {}
+        }
+    }
+
+    /// `palette` with the lit and unlit colors swapped when the display is inverted.
+    fn inverted(&self, palette: Palette) -> Palette {
+        if !self.invert {
+            return palette;
+        }
+        Palette {
+            active: palette.inactive,
+            inactive: palette.active,
+            ..palette
+        }
+    }
+
+    /// The slot the circle for `circle_row` goes in, where row 0 holds the least significant bit.
+    ///
+    /// Columns shorter than the grid line up with the others on the least significant bit.
+    fn slot(&self, grid: Grid, circle_row: u8) -> u8 {
+        match self.bit_order {
+            BitOrder::MsbTop => grid.rows - 1 - circle_row,
+            BitOrder::LsbTop => circle_row,
+        }
+    }
+
+    /// Draws a column, crossfading the bits that differ from the column in `transition`
+    /// by the factor that comes with it.
+    ///
+    /// A column that rolled over to a lower value, like the ones of the minutes going from 9 to 0,
+    /// also gets a band of light sweeping down it, so the rollover doesn't go unnoticed.
+    fn column(&self, frame: &mut canvas::Frame, index: usize, column: Column, transition: Option<(Column, f32)>, grid: Grid, palette: Palette) {
+        for circle_row in 0..column.rows {
+            let palette = self.gradient_palette(palette, circle_row, grid.rows);
+            let palette = if self.place_value_tint && !self.high_contrast {
+                tint(palette, circle_row)
+            } else {
+                palette
+            };
+            let palette = self.inverted(palette);
+            let position = grid.center(index, self.slot(grid, circle_row));
+            let active = column.is_lit(circle_row);
+            match transition {
+                Some((previous, factor)) if active != previous.is_lit(circle_row) => {
+                    // Lay the active color over the inactive look, as strong as the bit is lit
+                    let lit = if active { factor } else { 1.0 - factor };
+                    self.circle(frame, position, grid.radius, palette, false);
+                    let fill = Color {
+                        a: palette.active.a * lit,
+                        ..palette.active
+                    };
+                    frame.fill(&self.cell(position, grid.radius), fill);
+                }
+                _ => self.circle(frame, position, grid.radius, palette, active),
+            }
+            if self.show_place_values {
+                self.place_value(frame, position, grid.radius, circle_row, palette, active);
+            }
+        }
+
+        if let Some((previous, factor)) = transition.filter(|(previous, _)| column.value < previous.value) {
+            self.rollover_sweep(frame, index, grid, palette, factor);
+        }
+    }
+
+    /// Writes the place value of the bit in `circle_row` faintly inside the circle at `center`.
+    fn place_value(&self, frame: &mut canvas::Frame, center: Point, radius: f32, circle_row: u8, palette: Palette, active: bool) {
+        let content = (1u64 << circle_row).to_string();
+        // Long values get smaller to stay inside the circle
+        let size = (radius * 1.2).min(radius * 1.6 / text_width(&content, 1.0));
+        if size < MIN_PLACE_VALUE_TEXT {
+            return;
+        }
+        let color = if active { palette.background } else { palette.text };
+        centered_text(frame, center, content, size, Color { a: color.a * 0.5, ..color });
+    }
+
+    /// A band of light `factor` of the way down the column at `index`, fading as it goes.
+    fn rollover_sweep(&self, frame: &mut canvas::Frame, index: usize, grid: Grid, palette: Palette, factor: f32) {
+        let band = grid.radius;
+        let along = grid.along_start + (grid.along_length - band) * factor;
+        let top_left = grid.point(grid.column_start(index), along);
+        let size = match grid.orientation {
+            Orientation::Horizontal => Size::new(grid.radius * 2.0, band),
+            Orientation::Vertical => Size::new(band, grid.radius * 2.0),
+        };
+        let sweep = canvas::Path::rounded_rectangle(top_left, size, (band / 2.0).into());
+        frame.fill(&sweep, Color { a: 0.5 * (1.0 - factor), ..palette.pulse });
+    }
+
+    /// The part of `bounds` left for the columns, above the weekday strip and then the day progress bar.
+    fn clock_bounds(&self, bounds: Rectangle) -> Rectangle {
+        Rectangle {
+            height: bounds.height * (1.0 - self.below_ratio()),
+            ..bounds
+        }
+    }
+
+    /// Where `columns` go within `bounds`.
+    fn grid(&self, columns: &[Column], bounds: Rectangle) -> Grid {
+        let rows = columns.iter().map(|column| column.rows).max().unwrap_or(DEFAULT_ROWS);
+        Grid::new(self.orientation, self.alignment, columns, rows, self.spacing(), self.scale, self.clock_bounds(bounds))
+    }
+
+    /// Highlights the column at `index` and writes its value over it in a small bubble.
+    fn column_hint(&self, frame: &mut canvas::Frame, index: usize, column: Column, grid: Grid, palette: Palette) {
+        let top_left = grid.point(grid.column_start(index), grid.along_start);
+        let size = match grid.orientation {
+            Orientation::Horizontal => Size::new(grid.radius * 2.0, grid.along_length),
+            Orientation::Vertical => Size::new(grid.along_length, grid.radius * 2.0),
+        };
+        frame.fill(&canvas::Path::rounded_rectangle(top_left, size, grid.radius.into()), palette.hover);
+
+        let content = column.value.to_string();
+        let text_size = grid.radius * 1.2;
+        let center = Point::new(top_left.x + size.width / 2.0, top_left.y + size.height / 2.0);
+        let bubble = Size::new(text_width(&content, text_size) + text_size * 0.6, text_size * 1.2);
+        let bubble_corner = center - Vector::new(bubble.width / 2.0, bubble.height / 2.0);
+        frame.fill(
+            &canvas::Path::rounded_rectangle(bubble_corner, bubble, (bubble.height / 2.0).into()),
+            palette.background,
+        );
+        centered_text(frame, center, content, text_size, palette.text);
+    }
+
+    /// The room to leave between and around the circles.
+    fn spacing(&self) -> Spacing {
+        Spacing {
+            cells: self.cell_spacing,
+            // In a single row the fields would run into each other, so keep them apart
+            groups: if self.mode == DisplayMode::CompactRow {
+                self.group_gap.max(COMPACT_GROUP_GAP)
+            } else {
+                self.group_gap
+            },
+            padding: self.padding,
+            side: self.side_padding,
+        }
+    }
+
+    /// The size to draw the clock at in a panel `thickness` pixels thick, which runs
+    /// horizontally if `horizontal` is set. Along the panel it asks for just enough room to
+    /// fit every column at the size the panel's thickness allows.
+    pub fn preferred_size(&self, thickness: f32, horizontal: bool) -> Size {
+        let columns = self.columns();
+        let rows = columns.iter().map(|column| column.rows).max().unwrap_or(DEFAULT_ROWS);
+        let spacing = self.spacing();
+        let (span_across, span_along) = Grid::spans(&columns, rows, spacing);
+        // What goes below the columns always takes its share of the height, leaving the rest for them
+        let strip_ratio = self.below_ratio();
+        let full_thickness = thickness;
+        let thickness = if horizontal { thickness * (1.0 - strip_ratio) } else { thickness };
+        let padding = thickness * spacing.padding;
+        let length = if horizontal == (self.orientation == Orientation::Horizontal) {
+            // The columns run along the panel, sized by its thickness the way `Grid::new` sizes them
+            let radius = ((thickness - padding) / span_along * self.scale).min(thickness / span_along);
+            radius * span_across + spacing.side * 2.0
+        } else {
+            // The columns are stacked across the panel, so they are as wide as it is thick
+            let radius = (thickness - spacing.side * 2.0).max(0.0) / span_across;
+            radius * span_along / (1.0 - spacing.padding)
+        };
+        let length = if !self.show_weekday {
+            length
+        } else if horizontal {
+            // Wide enough for all seven days at the size the strip allows
+            let radius = full_thickness * WEEKDAY_STRIP_RATIO * (1.0 - PADDING_RATIO) / 2.0;
+            length.max(radius * Self::weekday_span())
+        } else {
+            length / (1.0 - strip_ratio)
+        };
+        let length = length.max(0.0).ceil();
+        if horizontal {
+            Size::new(length, full_thickness)
+        } else {
+            Size::new(full_thickness, length)
+        }
+    }
+
+    /// The share of the height taken up by the weekday strip and the day progress bar below the columns.
+    fn below_ratio(&self) -> f32 {
+        let weekday = if self.show_weekday { WEEKDAY_STRIP_RATIO } else { 0.0 };
+        let progress = if self.show_day_progress { DAY_PROGRESS_RATIO } else { 0.0 };
+        weekday + progress
+    }
+
+    /// How far through the day it is, from 0 at midnight to just under 1 right before the next.
+    fn day_fraction(&self) -> f32 {
+        self.current_time.num_seconds_from_midnight() as f32 / SECONDS_PER_DAY as f32
+    }
+
+    /// Draws the day progress bar into `bar`, with the part of the day that has passed filled in.
+    fn day_progress(&self, frame: &mut canvas::Frame, bar: Rectangle, palette: Palette) {
+        // Thinner than its share of the height, so it doesn't touch the circles above it
+        let height = bar.height / 2.0;
+        let margin = height;
+        let width = bar.width - margin * 2.0;
+        if height < MIN_RADIUS || width <= 0.0 {
+            return;
+        }
+        let top_left = Point::new(bar.x + margin, bar.center_y() - height / 2.0);
+        let radius = (height / 2.0).into();
+        frame.fill(&canvas::Path::rounded_rectangle(top_left, Size::new(width, height), radius), palette.inactive);
+        let filled = width * self.day_fraction();
+        if filled > 0.0 {
+            frame.fill(&canvas::Path::rounded_rectangle(top_left, Size::new(filled, height), radius), palette.active);
+        }
+    }
+
+    /// How many radii the seven circles of the weekday strip take up, gaps included.
+    fn weekday_span() -> f32 {
+        7.0 * 2.0 + 6.0 * WEEKDAY_SPACING
+    }
+
+    /// Which of the circles of the weekday strip is today's, counting from the first day of the week.
+    fn weekday_circle(&self) -> usize {
+        let first = self.first_weekday.num_days_from_monday();
+        ((self.current_time.weekday().num_days_from_monday() + 7 - first) % 7) as usize
+    }
+
+    /// Draws a circle for every day of the week into `strip`, starting with the first day of the
+    /// week, with today's lit.
+    fn weekday(&self, frame: &mut canvas::Frame, strip: Rectangle, palette: Palette) {
+        let radius = (strip.height * (1.0 - PADDING_RATIO) / 2.0).min(strip.width / Self::weekday_span());
+        if radius.is_nan() || radius < MIN_RADIUS {
+            return;
+        }
+        let pitch = radius * (2.0 + WEEKDAY_SPACING);
+        let start = strip.x + (strip.width - radius * Self::weekday_span()) / 2.0 + radius;
+        // Circles are counted from the first day of the week, labels from Monday
+        let first = self.first_weekday.num_days_from_monday() as usize;
+        let today = self.weekday_circle();
+        let size = radius * 1.2;
+        for day in 0..7 {
+            let center = Point::new(start + pitch * day as f32, strip.center_y());
+            let active = day == today;
+            self.circle(frame, center, radius, palette, active);
+            let Some(label) = self.weekday_labels.as_ref().map(|labels| labels[(first + day) % 7].clone()) else {
+                continue;
+            };
+            let color = if active { palette.background } else { palette.text };
+            centered_text(frame, center, label, size, color);
+        }
+    }
+
+    /// The hour to display, and whether it is after noon.
+    ///
+    /// In twelve hour mode midnight is shown as 12 AM and noon as 12 PM.
+    fn hour(&self) -> (u32, bool) {
+        if self.twelve_hour {
+            let (is_pm, hour) = self.current_time.hour12();
+            (hour, is_pm)
+        } else {
+            (self.current_time.hour(), self.current_time.hour() >= 12)
+        }
+    }
+
+    /// The columns to draw for the current time, from left to right.
+    fn columns(&self) -> Vec<Column> {
+        if self.day_of_month {
+            return vec![Column::up_to(Field::Day, self.current_time.day(), MAX_DAY)];
+        }
+
+        let (hour, is_pm) = self.hour();
+        let minute = self.current_time.minute();
+        let second = self.current_time.second();
+
+        let max_hour = if self.twelve_hour { 12 } else { 23 };
+
+        if self.compact {
+            return vec![
+                Column::up_to(Field::Hour, hour, max_hour),
+                Column::up_to(Field::Minute, minute, MAX_MINUTE),
+            ];
+        }
+
+        let mut columns = match self.mode {
+            DisplayMode::BCD => {
+                // The remaining columns are laid out from however many are left, so they re-center
+                let [tens, ones] = Column::digits(Field::Hour, hour, max_hour);
+                let mut columns = if self.blank_leading_zero && tens.value == 0 {
+                    vec![ones]
+                } else {
+                    vec![tens, ones]
+                };
+                columns.extend(Column::digits(Field::Minute, minute, MAX_MINUTE));
+                if self.show_seconds {
+                    columns.extend(Column::digits(Field::Second, second, MAX_MINUTE));
+                }
+                columns
+            }
+            DisplayMode::BinaryHMS => {
+                let mut columns = vec![
+                    Column::up_to(Field::Hour, hour, max_hour),
+                    Column::up_to(Field::Minute, minute, MAX_MINUTE),
+                ];
+                if self.show_seconds {
+                    columns.push(Column::up_to(Field::Second, second, MAX_MINUTE));
+                }
+                columns
+            }
+            DisplayMode::Mixed => {
+                // The hour column is taller than the digits next to it, and they all share a baseline
+                let mut columns = vec![Column::up_to(Field::Hour, hour, max_hour)];
+                columns.extend(Column::digits(Field::Minute, minute, MAX_MINUTE));
+                if self.show_seconds {
+                    columns.extend(Column::digits(Field::Second, second, MAX_MINUTE));
+                }
+                columns
+            }
+            DisplayMode::CompactRow => {
+                let mut columns: Vec<Column> = Column::bits(Field::Hour, hour, bits_needed(max_hour)).collect();
+                columns.extend(Column::bits(Field::Minute, minute, bits_needed(MAX_MINUTE)));
+                if self.show_seconds {
+                    columns.extend(Column::bits(Field::Second, second, bits_needed(MAX_MINUTE)));
+                }
+                columns
+            }
+            DisplayMode::BinarySeconds => Column::bits(Field::Second, second, bits_needed(MAX_MINUTE)).collect(),
+            DisplayMode::HEX => {
+                // Hexadecimal time splits the day into 16^4 units, so 8000 is noon
+                let seconds = u64::from(self.current_time.num_seconds_from_midnight());
+                let hex_time = (seconds * 0x10000 / SECONDS_PER_DAY) as u32;
+                (0..4)
+                    .rev()
+                    .map(|digit| Column::up_to(Field::DayFraction, (hex_time >> (digit * 4)) & 0xf, 0xf))
+                    .collect()
+            }
+            DisplayMode::UnixEpoch => {
+                // Only the bits that are shown matter, so truncating is fine
+                let timestamp = self.current_time.timestamp() as u32;
+                Column::bits(Field::Epoch, timestamp, self.epoch_bits).collect()
+            }
+            DisplayMode::Stopwatch => {
+                let seconds = self.elapsed.as_secs().min(MAX_STOPWATCH_HOUR * 3600 + 3599);
+                let mut columns = Vec::from(Column::digits(Field::Hour, (seconds / 3600) as u32, MAX_STOPWATCH_HOUR as u32));
+                columns.extend(Column::digits(Field::Minute, (seconds / 60 % 60) as u32, MAX_MINUTE));
+                columns.extend(Column::digits(Field::Second, (seconds % 60) as u32, MAX_MINUTE));
+                columns
+            }
+            DisplayMode::Countdown => {
+                // Rounded up, so the countdown shows 00:00 only once it is over, unless the tenths
+                // of a second after it show what is left of the last one
+                let seconds = if self.show_deciseconds {
+                    self.remaining.as_secs()
+                } else {
+                    self.remaining.as_millis().div_ceil(1000) as u64
+                };
+                let seconds = seconds.min(MAX_COUNTDOWN_MINUTE * 60 + 59);
+                let mut columns = Vec::from(Column::digits(Field::Minute, (seconds / 60) as u32, MAX_COUNTDOWN_MINUTE as u32));
+                columns.extend(Column::digits(Field::Second, (seconds % 60) as u32, MAX_MINUTE));
+                columns
+            }
+        };
+
+        if self.show_deciseconds && columns.iter().any(|column| column.field == Field::Second) {
+            let tenths = if self.mode == DisplayMode::Stopwatch {
+                self.elapsed.subsec_millis() / 100
+            } else if self.mode == DisplayMode::Countdown {
+                self.remaining.subsec_millis() / 100
+            } else {
+                // A leap second counts its nanoseconds past a billion
+                (self.current_time.nanosecond() / 100_000_000).min(9)
+            };
+            columns.push(Column::up_to(Field::Decisecond, tenths, 9));
+        }
+
+        if self.shows_meridiem(&columns) && self.meridiem_style == MeridiemStyle::Column {
+            // The AM/PM bit gets a column of its own, after the seconds
+            columns.push(Column::new(Field::Meridiem, u32::from(is_pm), 1));
+        }
+
+        if self.mirror {
+            columns.reverse();
+        }
+
+        columns
+    }
+
+    /// Whether an AM/PM indicator belongs next to these columns.
+    fn shows_meridiem(&self, columns: &[Column]) -> bool {
+        // Elapsed hours are neither morning nor afternoon
+        self.twelve_hour && self.mode != DisplayMode::Stopwatch && columns.iter().any(|column| column.field == Field::Hour)
+    }
+
+    /// A small AM/PM dot in the top right corner, which leaves the layout of the columns alone.
+    fn meridiem_dot(&self, frame: &mut canvas::Frame, grid: Grid, palette: Palette) {
+        let radius = grid.radius * 0.35;
+        let center = Point::new(frame.width() - radius * 1.5, radius * 1.5);
+        self.circle(frame, center, radius, palette, self.hour().1);
+    }
+
+    /// A fading glow over the lowest bit of the last time column, which is seconds when they are shown.
+    fn heartbeat(&self, columns: &[Column], grid: Grid, palette: Palette, renderer: &Renderer, bounds: Rectangle) -> Option<canvas::Frame> {
+        let index = columns.iter().rposition(|column| {
+            column.field != Field::Meridiem && !(column.field == Field::Second && self.shows_seconds_ring())
+        })?;
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        let glow = self.cell(grid.center(index, self.slot(grid, 0)), grid.radius);
+        frame.fill(&glow, Color { a: self.heartbeat * 0.6, ..palette.pulse });
+        Some(frame)
+    }
+
+    /// Lines in the gaps where one field ends and the next begins.
+    fn separators(&self, frame: &mut canvas::Frame, columns: &[Column], grid: Grid, palette: Palette) {
+        let stroke = canvas::Stroke::default()
+            .with_width(self.stroke_width(grid.radius))
+            .with_color(palette.separator);
+        for (index, pair) in columns.windows(2).enumerate() {
+            if pair[0].field == pair[1].field {
+                continue;
+            }
+            let across = grid.gap_center(index + 1);
+            let start = grid.point(across, grid.along_start);
+            let end = grid.point(across, grid.along_start + grid.along_length);
+            frame.stroke(&canvas::Path::line(start, end), stroke);
+        }
+    }
+
+    /// Whether the seconds columns make room for the seconds ring rather than being drawn.
+    fn shows_seconds_ring(&self) -> bool {
+        self.seconds_as_ring && !matches!(self.mode, DisplayMode::Stopwatch | DisplayMode::Countdown)
+    }
+
+    /// The seconds as a ring where their columns would be, filled clockwise from the top as far
+    /// as the minute has passed.
+    fn seconds_ring(&self, frame: &mut canvas::Frame, columns: &[Column], grid: Grid, palette: Palette) {
+        let Some(first) = columns.iter().position(|column| column.field == Field::Second) else {
+            return;
+        };
+        let last = columns.iter().rposition(|column| column.field == Field::Second).unwrap_or(first);
+        let start = grid.column_start(first);
+        let end = grid.column_start(last) + grid.radius * 2.0;
+        let width = grid.radius / 2.0;
+        let radius = ((end - start).min(grid.along_length) - width) / 2.0;
+        if radius < MIN_RADIUS {
+            return;
+        }
+        let center = grid.point((start + end) / 2.0, grid.along_start + grid.along_length / 2.0);
+        let palette = self.inverted(self.field_palette(palette, Field::Second));
+        let stroke = canvas::Stroke::default().with_width(width);
+        frame.stroke(&canvas::Path::circle(center, radius), stroke.with_color(palette.inactive));
+
+        let passed = self.current_time.second() as f32 / 60.0;
+        if passed > 0.0 {
+            // Angles run clockwise from three o'clock, so start a quarter turn back, at twelve
+            let top = -std::f32::consts::FRAC_PI_2;
+            let arc = canvas::Path::new(|builder| {
+                builder.arc(canvas::path::Arc {
+                    center,
+                    radius,
+                    start_angle: Radians(top),
+                    end_angle: Radians(top + passed * std::f32::consts::TAU),
+                });
+            });
+            frame.stroke(&arc, stroke.with_color(palette.active));
+        }
+    }
+
+    /// A colon in the gap between the hours and the minutes, like on a digital clock. It blinks,
+    /// so it is only drawn on even seconds.
+    fn blinking_separator(&self, frame: &mut canvas::Frame, columns: &[Column], grid: Grid, palette: Palette) {
+        if self.current_time.second() % 2 != 0 {
+            return;
+        }
+        let Some(index) = columns.windows(2).position(|pair| {
+            matches!((pair[0].field, pair[1].field), (Field::Hour, Field::Minute) | (Field::Minute, Field::Hour))
+        }) else {
+            return;
+        };
+        let across = grid.gap_center(index + 1);
+        let radius = grid.radius * 0.25;
+        for share in [1.0 / 3.0, 2.0 / 3.0] {
+            let dot = grid.point(across, grid.along_start + grid.along_length * share);
+            frame.fill(&canvas::Path::circle(dot, radius), palette.active);
+        }
+    }
+
+    /// The value of every column of more than one bit, written below it (or to the right of it
+    /// when vertical), in the padding outside the grid.
+    fn digit_labels(&self, frame: &mut canvas::Frame, columns: &[Column], grid: Grid, palette: Palette) {
+        let along = grid.along_start + grid.along_length;
+        let room = grid.along - along;
+        let size = (grid.radius * 1.2).min(room * 0.9);
+        if size < 1.0 {
+            return;
+        }
+        for (index, column) in columns.iter().enumerate().filter(|(_, column)| column.rows > 1) {
+            let content = column.value.to_string();
+            let across = grid.column_start(index) + grid.radius;
+            // Right below the column, or when vertical, starting a little to the right of it
+            let center = match grid.orientation {
+                Orientation::Horizontal => grid.point(across, along + size / 2.0),
+                Orientation::Vertical => grid.point(across, along + size * 0.1 + text_width(&content, size) / 2.0),
+            };
+            centered_text(frame, center, content, size, palette.text);
+        }
+    }
+
+    /// A fingerprint of everything the cached part of the clock depends on, so it is only drawn
+    /// again when what it shows changes, rather than on every frame.
+    fn cache_key(&self, columns: &[Column], previous: &[Column], palette: Palette, size: Size, state: &ClockState) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (columns, previous, state.hovered, state.hovered_column, self.highlighted_column).hash(&mut hasher);
+        (self.shows_meridiem(columns) && self.hour().1).hash(&mut hasher);
+        (self.day_of_month, self.compact, self.mode, self.orientation, self.alignment, self.cell_shape, self.inactive_style, self.bit_order, self.meridiem_style).hash(&mut hasher);
+        (self.show_labels, self.show_background, self.show_digit_labels, self.place_value_tint, self.show_place_values).hash(&mut hasher);
+        (self.high_contrast, self.invert, self.cell_border, self.gradient.is_some(), self.shows_seconds_ring()).hash(&mut hasher);
+        (self.show_weekday.then(|| (self.current_time.weekday(), self.first_weekday)), &self.weekday_labels).hash(&mut hasher);
+        self.blink_separator.then(|| self.current_time.second() % 2).hash(&mut hasher);
+        self.show_day_progress.then(|| self.current_time.num_seconds_from_midnight()).hash(&mut hasher);
+        let colors = [palette.active, palette.inactive, palette.separator, palette.background, palette.hover]
+            .into_iter()
+            .chain(self.field_colors.into_iter().flatten().flatten())
+            .chain(self.gradient.into_iter().flatten().flatten())
+            .flat_map(|color| [color.r, color.g, color.b, color.a]);
+        for value in [self.cell_spacing, self.group_gap, self.scale, self.padding, self.side_padding, self.brightness, self.fade, size.width, size.height].into_iter().chain(colors) {
+            value.to_bits().hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+}
+
+impl<Message: Clone> cosmic::widget::canvas::Program<Message, cosmic::Theme> for BinaryClock<Message> {
+    type State = ClockState;
+
+    /// Tracks hovering and turns clicks into messages:
+    ///
+    /// - a left click emits the message set with [`BinaryClock::on_press`]
+    /// - a right click emits the message set with [`BinaryClock::on_right_press`]
+    /// - every notch of scrolling emits one of the messages set with [`BinaryClock::on_scroll`]
+    fn update(
+        &self,
+        state: &mut ClockState,
+        event: canvas::Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (canvas::event::Status, Option<Message>) {
+        state.hovered = cursor.is_over(bounds);
+        if self.column_hints {
+            let columns = self.columns();
+            state.hovered_column = cursor
+                .position_in(bounds)
+                .and_then(|point| self.grid(&columns, bounds).column_at(point, columns.len()));
+        }
+
+        match event {
+            canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+                if state.hovered && self.on_press.is_some() =>
+            {
+                (canvas::event::Status::Captured, self.on_press.clone())
+            }
+            canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Right))
+                if state.hovered && self.on_right_press.is_some() =>
+            {
+                (canvas::event::Status::Captured, self.on_right_press.clone())
+            }
+            canvas::Event::Mouse(mouse::Event::WheelScrolled { delta }) if state.hovered => {
+                let Some((down, up)) = &self.on_scroll else {
+                    return (canvas::event::Status::Ignored, None);
+                };
+                state.scrolled += match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => y,
+                    mouse::ScrollDelta::Pixels { y, .. } => y / PIXELS_PER_SCROLL_NOTCH,
+                };
+                // Touchpads scroll a little at a time, so wait until that adds up to a notch
+                if state.scrolled.abs() < 1.0 {
+                    return (canvas::event::Status::Captured, None);
+                }
+                let message = if state.scrolled < 0.0 { down } else { up };
+                state.scrolled = 0.0;
+                (canvas::event::Status::Captured, Some(message.clone()))
+            }
+            _ => (canvas::event::Status::Ignored, None),
+        }
+    }
+
+    fn draw(
+        &self,
+        state: &ClockState,
+        renderer: &Renderer,
+        theme: &cosmic::Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let columns = self.columns();
+        let grid = self.grid(&columns, bounds);
+        let strip_height = if self.show_weekday { bounds.height * WEEKDAY_STRIP_RATIO } else { 0.0 };
+        let clock_bounds = self.clock_bounds(bounds);
+        let strip = Rectangle::new(Point::new(0.0, clock_bounds.height), Size::new(bounds.width, strip_height));
+        let progress_bar = Rectangle::new(
+            Point::new(0.0, clock_bounds.height + strip_height),
+            Size::new(bounds.width, bounds.height * DAY_PROGRESS_RATIO),
+        );
+        // A panel that is still being resized can be too small to hold anything, which would
+        // make for a zero, negative or NaN radius. Draw nothing until there is room again.
+        if columns.is_empty() || grid.radius.is_nan() || grid.radius < MIN_RADIUS {
+            return Vec::new();
+        }
+
+        let palette = self.palette(theme);
+        let previous = self
+            .previous_time
+            .map(|time| Self { current_time: time, ..self.clone() }.columns())
+            .unwrap_or_default();
+
+        // The cache outlives this widget, which is built anew on every view, so it is told apart by
+        // what it shows. That also covers configuration changes, which change what is shown.
+        let key = self.cache_key(&columns, &previous, palette, bounds.size(), state);
+        if state.cached.replace(Some(key)) != Some(key) {
+            state.cache.clear();
+        }
+
+        let clock = state.cache.draw(renderer, bounds.size(), |frame| {
+            let outline = canvas::Path::rounded_rectangle(Point::ORIGIN, bounds.size(), grid.radius.into());
+
+            // First, so everything else is drawn on top of it
+            if self.show_background {
+                frame.fill(&outline, palette.background);
+            }
+
+            if state.hovered {
+                frame.fill(&outline, palette.hover);
+            }
+
+            for (index, column) in columns.iter().enumerate() {
+                if column.field == Field::Second && self.shows_seconds_ring() {
+                    continue;
+                }
+                // Only fade between columns that show the same thing, not across a change of layout
+                let transition = previous
+                    .get(index)
+                    .filter(|from| previous.len() == columns.len() && from.field == column.field && from.rows == column.rows)
+                    .map(|from| (*from, self.fade));
+                let palette = self.field_palette(palette, column.field);
+                self.column(frame, index, *column, transition, grid, palette);
+            }
+
+            if self.shows_seconds_ring() {
+                self.seconds_ring(frame, &columns, grid, palette);
+            }
+
+            if self.show_labels {
+                self.separators(frame, &columns, grid, palette);
+            }
+
+            if self.blink_separator {
+                self.blinking_separator(frame, &columns, grid, palette);
+            }
+
+            if self.show_digit_labels {
+                self.digit_labels(frame, &columns, grid, palette);
+            }
+
+            if self.shows_meridiem(&columns) && self.meridiem_style == MeridiemStyle::CornerDot {
+                self.meridiem_dot(frame, grid, palette);
+            }
+
+            // Last, so nothing covers up the value
+            if let Some(index) = state.hovered_column.or(self.highlighted_column).filter(|index| *index < columns.len()) {
+                self.column_hint(frame, index, columns[index], grid, palette);
+            }
+
+            if self.show_weekday {
+                self.weekday(frame, strip, palette);
+            }
+
+            if self.show_day_progress {
+                self.day_progress(frame, progress_bar, palette);
+            }
+        });
+
+        // The animations change every frame, so there is no point caching them
+        let mut geometry = vec![clock];
+
+        if self.heartbeat > 0.0 {
+            geometry.extend(self.heartbeat(&columns, grid, palette, renderer, bounds).map(canvas::Frame::into_geometry));
+        }
+
+        // Last, so the flash brightens everything below it
+        if self.minute_pulse > 0.0 {
+            let mut frame = canvas::Frame::new(renderer, bounds.size());
+            let flash = canvas::Path::rounded_rectangle(Point::ORIGIN, bounds.size(), grid.radius.into());
+            frame.fill(&flash, Color { a: self.minute_pulse * 0.25, ..palette.pulse });
+            geometry.push(frame.into_geometry());
+        }
+
+        geometry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    // Slack for rounding in the layout arithmetic, in pixels
+    const EPSILON: f32 = 1e-3;
+
+    /// A clock frozen at `hour:minute:second` on an ordinary Thursday, in UTC.
+    fn clock_at(hour: u32, minute: u32, second: u32) -> BinaryClock<()> {
+        let time = FixedOffset::east_opt(0)
+            .unwrap()
+            .with_ymd_and_hms(2024, 3, 14, hour, minute, second)
+            .unwrap();
+        BinaryClock::new(time)
+    }
+
+    /// Panics unless every circle of `clock`, drawn `size` big, lies within its bounds.
+    fn assert_circles_fit(clock: &BinaryClock<()>, size: Size) {
+        let columns = clock.columns();
+        let grid = clock.grid(&columns, Rectangle::with_size(size));
+        for (index, column) in columns.iter().enumerate() {
+            for row in 0..column.rows {
+                let center = grid.center(index, clock.slot(grid, row));
+                let radius = grid.radius;
+                assert!(
+                    center.x - radius >= -EPSILON
+                        && center.y - radius >= -EPSILON
+                        && center.x + radius <= size.width + EPSILON
+                        && center.y + radius <= size.height + EPSILON,
+                    "circle {row} of column {index} at {center:?} with radius {radius} leaves {size:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn circles_fit_the_bounds_at_any_padding() {
+        let sizes = [(100.0, 20.0), (40.0, 40.0), (300.0, 32.0), (24.0, 200.0), (1.0, 1.0)];
+        for (width, height) in sizes {
+            for padding in [0.0, PADDING_RATIO, MAX_PADDING] {
+                for scale in [MIN_SCALE, 1.0, MAX_SCALE] {
+                    let clock = clock_at(23, 59, 58).padding(padding).scale(scale);
+                    assert_circles_fit(&clock, Size::new(width, height));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_cell_shape_draws_something() {
+        for shape in CellShape::ALL {
+            let cell = clock_at(12, 0, 0).cell_shape(shape).cell(Point::new(10.0, 10.0), 8.0);
+            assert!(cell.raw().iter().next().is_some(), "{shape:?} cells are empty");
+        }
+    }
+
+    #[test]
+    fn bit_order_flips_which_slots_are_lit() {
+        // The ones of the minutes show 5, 0b0101, in a column of four
+        for (bit_order, expected) in [(BitOrder::MsbTop, [1, 3]), (BitOrder::LsbTop, [0, 2])] {
+            let clock = clock_at(0, 5, 0).bit_order(bit_order);
+            let columns = clock.columns();
+            let ones = columns[3];
+            assert_eq!((ones.value, ones.rows), (5, 4));
+            let grid = clock.grid(&columns, Rectangle::with_size(Size::new(100.0, 40.0)));
+            let mut lit: Vec<u8> = (0..ones.rows)
+                .filter(|row| ones.is_lit(*row))
+                .map(|row| clock.slot(grid, row))
+                .collect();
+            lit.sort();
+            assert_eq!(lit, expected, "{bit_order:?}");
+        }
+    }
+
+    #[test]
+    fn bcd_columns_light_the_digits_of_the_time() {
+        let times = [
+            ((23, 59, 58), [2, 3, 5, 9, 5, 8]),
+            ((0, 0, 0), [0, 0, 0, 0, 0, 0]),
+            ((12, 34, 56), [1, 2, 3, 4, 5, 6]),
+        ];
+        for ((hour, minute, second), expected) in times {
+            let clock = clock_at(hour, minute, second).mode(DisplayMode::BCD);
+            assert_eq!(clock.column_count(), 6);
+            for (column, digit) in expected.into_iter().enumerate() {
+                for row in 0..4 {
+                    assert_eq!(
+                        clock.bit_at(column as u8, row),
+                        is_set(digit, row),
+                        "bit {row} of column {column} at {hour:02}:{minute:02}:{second:02}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn digits_split_tens_and_ones() {
+        assert_eq!(digits(0), (0, 0));
+        assert_eq!(digits(9), (0, 9));
+        assert_eq!(digits(58), (5, 8));
+        assert!(is_set(0b0101, 0) && !is_set(0b0101, 1) && is_set(0b0101, 2));
+    }
+
+    #[test]
+    fn bit_centers_stay_inside_extreme_bounds() {
+        for (width, height) in [(1000.0, 10.0), (10.0, 1000.0)] {
+            for orientation in [Orientation::Horizontal, Orientation::Vertical] {
+                let clock = clock_at(23, 59, 58).orientation(orientation);
+                let size = Size::new(width, height);
+                let columns = clock.columns();
+                let radius = clock.grid(&columns, Rectangle::with_size(size)).radius;
+                for (column, bits) in columns.iter().enumerate() {
+                    for row in 0..bits.rows {
+                        let center = clock
+                            .bit_center(column as u8, row, size)
+                            .unwrap_or_else(|| panic!("no room for column {column} in {size:?}, {orientation:?}"));
+                        assert!(
+                            center.x - radius >= -EPSILON
+                                && center.y - radius >= -EPSILON
+                                && center.x + radius <= width + EPSILON
+                                && center.y + radius <= height + EPSILON,
+                            "bit {row} of column {column} at {center:?} leaves {size:?}, {orientation:?}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn mirror_reverses_the_columns() {
+        let values = |clock: &BinaryClock<()>| -> Vec<u32> {
+            (0..clock.column_count()).filter_map(|column| clock.column_value(column)).map(|(value, _)| value).collect()
+        };
+        assert_eq!(values(&clock_at(12, 34, 56)), [1, 2, 3, 4, 5, 6]);
+        assert_eq!(values(&clock_at(12, 34, 56).mirror(true)), [6, 5, 4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn weekday_strip_lights_today_from_either_week_start() {
+        // 14 March 2024 is a Thursday, the fourth day from Monday and the fifth from Sunday
+        assert_eq!(clock_at(12, 0, 0).first_weekday(Weekday::Mon).weekday_circle(), 3);
+        assert_eq!(clock_at(12, 0, 0).first_weekday(Weekday::Sun).weekday_circle(), 4);
+        // Sunday itself ends a week starting on Monday, and starts one starting on Sunday
+        let sunday = FixedOffset::east_opt(0).unwrap().with_ymd_and_hms(2024, 3, 17, 12, 0, 0).unwrap();
+        assert_eq!(BinaryClock::<()>::new(sunday).first_weekday(Weekday::Mon).weekday_circle(), 6);
+        assert_eq!(BinaryClock::<()>::new(sunday).first_weekday(Weekday::Sun).weekday_circle(), 0);
+    }
+
+    #[test]
+    fn columns_are_as_tall_as_their_values_need() {
+        let rows = |clock: BinaryClock<()>| -> Vec<u8> {
+            (0..clock.column_count()).filter_map(|column| clock.column_value(column)).map(|(_, rows)| rows).collect()
+        };
+        // Tens of hours only go up to 2, tens of minutes and seconds up to 5, and ones up to 9
+        assert_eq!(rows(clock_at(23, 59, 58)), [2, 4, 3, 4, 3, 4]);
+        // The same at midnight, when every column shows zero
+        assert_eq!(rows(clock_at(0, 0, 0)), [2, 4, 3, 4, 3, 4]);
+        // Twelve hour times never get past 1 in the tens of hours, and AM/PM takes a single bit
+        assert_eq!(rows(clock_at(23, 59, 58).twelve_hour(true)), [1, 4, 3, 4, 3, 4, 1]);
+        // A whole field to a column: hours up to 23, minutes and seconds up to 59
+        assert_eq!(rows(clock_at(23, 59, 58).mode(DisplayMode::BinaryHMS)), [5, 6, 6]);
+    }
+
+    /// Draws a frozen clock with the software renderer and checks that the pixel at every
+    /// [`BinaryClock::bit_center`] has the lit color exactly where [`BinaryClock::bit_at`] says so.
+    ///
+    /// Needs a renderer that can draw without a window, so it only runs with the `render-tests`
+    /// feature: `cargo test --features render-tests`.
+    #[cfg(feature = "render-tests")]
+    #[test]
+    fn rendered_bits_match_bit_at() {
+        use cosmic::iced::Pixels;
+        use cosmic::iced::advanced::graphics::geometry::Renderer as _;
+        use cosmic::iced::advanced::renderer::Headless;
+        use cosmic::iced::widget::canvas::Program;
+
+        let (width, height) = (120, 40);
+        let size = Size::new(width as f32, height as f32);
+        let lit = Color::from_rgb(1.0, 0.0, 0.0);
+        let unlit = Color::from_rgb(0.0, 0.0, 1.0);
+        let clock = clock_at(12, 34, 56).active_color(Some(lit)).inactive_color(Some(unlit));
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let mut renderer = runtime
+            .block_on(Renderer::new(cosmic::iced::Font::default(), Pixels(16.0), Some("tiny-skia")))
+            .expect("no software renderer");
+        let geometry = clock.draw(
+            &ClockState::default(),
+            &renderer,
+            &cosmic::Theme::dark(),
+            Rectangle::with_size(size),
+            mouse::Cursor::Unavailable,
+        );
+        for layer in geometry {
+            renderer.draw_geometry(layer);
+        }
+        // Four bytes per pixel, red, green, blue and alpha, row by row
+        let pixels = renderer.screenshot(Size::new(width, height), 1.0, Color::BLACK);
+
+        for column in 0..clock.column_count() as u8 {
+            for row in 0..clock.column_value(column.into()).unwrap().1 {
+                let center = clock.bit_center(column, row, size).unwrap();
+                let offset = (center.y as usize * width as usize + center.x as usize) * 4;
+                let (r, b) = (pixels[offset], pixels[offset + 2]);
+                let drawn_lit = r > 200 && b < 50;
+                let drawn_unlit = b > 200 && r < 50;
+                assert!(drawn_lit || drawn_unlit, "bit {row} of column {column} is neither color: {r} {b}");
+                assert_eq!(drawn_lit, clock.bit_at(column, row), "bit {row} of column {column}");
+            }
+        }
+    }
+}
@@ -1,9 +1,803 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use cosmic::cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry};
+use chrono::{DateTime, FixedOffset, Local, NaiveTime, Weekday};
+use chrono_tz::Tz;
+use cosmic::iced::Color;
+use cosmic::cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, ConfigGet, CosmicConfigEntry};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::time::Duration;
 
-#[derive(Debug, Default, Clone, CosmicConfigEntry, Eq, PartialEq)]
+/// The narrowest and widest the settings popup may be, in logical pixels.
+pub const POPUP_WIDTH_RANGE: (u32, u32) = (300, 800);
+/// The shortest and tallest the settings popup may be, in logical pixels.
+pub const POPUP_HEIGHT_RANGE: (u32, u32) = (200, 1080);
+/// The fewest and most low bits of the Unix timestamp shown in epoch mode.
+pub const EPOCH_BITS_RANGE: (u32, u32) = (8, 32);
+/// The shortest tick interval that can be configured, in milliseconds.
+pub const MIN_TICK_INTERVAL_MS: u32 = 50;
+/// The shortest and longest countdown that can be set, in minutes.
+pub const COUNTDOWN_MINUTES_RANGE: (u32, u32) = (1, 99);
+/// The fixed UTC offset setting of version 1, in seconds east of Greenwich, that `timezone` replaced.
+const LEGACY_OFFSET_KEY: &str = "offset_seconds";
+
+/// How the current time is encoded into columns of circles.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum DisplayMode {
+    /// One column per decimal digit (binary coded decimal).
+    #[default]
+    BCD,
+    /// One column per field, holding the full binary value of that field.
+    #[serde(alias = "BINARY")]
+    BinaryHMS,
+    /// Only the seconds, as a single row of large bits.
+    BinarySeconds,
+    /// The fraction of the day that has passed, as four hexadecimal digits.
+    HEX,
+    /// The hour as a single binary column, with minutes and seconds as binary coded decimal.
+    Mixed,
+    /// Hours, minutes and seconds in binary, one after the other in a single row of bits.
+    CompactRow,
+    /// The time since the stopwatch was started, as binary coded decimal.
+    Stopwatch,
+    /// The low bits of the Unix timestamp, in a single row.
+    UnixEpoch,
+    /// The time left on a countdown, as minutes and seconds in binary coded decimal.
+    Countdown,
+}
+
+impl DisplayMode {
+    /// Every mode, in the order they are offered in the settings.
+    pub const ALL: [Self; 9] = [
+        Self::BCD,
+        Self::BinaryHMS,
+        Self::Mixed,
+        Self::CompactRow,
+        Self::BinarySeconds,
+        Self::HEX,
+        Self::UnixEpoch,
+        Self::Stopwatch,
+        Self::Countdown,
+    ];
+}
+
+/// The shape each bit is drawn as.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum CellShape {
+    #[default]
+    Circle,
+    /// Square cells, like an LED matrix.
+    Square,
+    RoundedSquare,
+}
+
+impl CellShape {
+    /// Every shape, in the order they are offered in the settings.
+    pub const ALL: [Self; 3] = [Self::Circle, Self::Square, Self::RoundedSquare];
+}
+
+/// How bits that are off are drawn.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum InactiveStyle {
+    /// Filled with the inactive color.
+    #[default]
+    Filled,
+    /// Only the outline, so they read as empty.
+    Outline,
+    /// Not drawn at all.
+    Hidden,
+}
+
+impl InactiveStyle {
+    /// Every style, in the order they are offered in the settings.
+    pub const ALL: [Self; 3] = [Self::Filled, Self::Outline, Self::Hidden];
+}
+
+/// A built-in pair of lit and unlit colors, to start from before tweaking them one by one.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Preset {
+    /// Bright green on near black, like a terminal in the films.
+    Matrix,
+    /// Warm amber on dark brown, like an old monochrome monitor.
+    AmberCrt,
+    /// Pale cyan on deep blue.
+    IceBlue,
+}
+
+impl Preset {
+    /// Every preset, in the order they are offered in the settings.
+    pub const ALL: [Self; 3] = [Self::Matrix, Self::AmberCrt, Self::IceBlue];
+
+    /// The lit and unlit colors, as they are stored in [`Config::active_color`] and
+    /// [`Config::inactive_color`].
+    pub fn colors(self) -> [&'static str; 2] {
+        match self {
+            Self::Matrix => ["#00ff41", "#0d2b12"],
+            Self::AmberCrt => ["#ffb000", "#3a2600"],
+            Self::IceBlue => ["#a5f2ff", "#12303a"],
+        }
+    }
+}
+
+/// Which end of a column the most significant bit is drawn at.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum BitOrder {
+    /// Most significant bit at the top (or left, when vertical).
+    #[default]
+    MsbTop,
+    /// Least significant bit at the top (or left, when vertical).
+    LsbTop,
+}
+
+/// How the AM/PM indicator is drawn in twelve hour mode.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum MeridiemStyle {
+    /// A column of its own after the seconds, lit in the afternoon.
+    #[default]
+    Column,
+    /// A small dot in the top right corner that doesn't shift the other columns.
+    CornerDot,
+}
+
+/// Where the columns sit across the room they are given, when it is more than they fill.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum ClockAlignment {
+    /// Against the left edge, or the top one when vertical.
+    Left,
+    #[default]
+    Center,
+    /// Against the right edge, or the bottom one when vertical.
+    Right,
+}
+
+impl ClockAlignment {
+    /// Every alignment, in the order they are offered in the settings.
+    pub const ALL: [Self; 3] = [Self::Left, Self::Center, Self::Right];
+}
+
+/// The day the weekday strip starts with.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum WeekStart {
+    /// Whichever day the week usually starts on in the locale, looked up each time it is shown.
+    #[default]
+    Locale,
+    Monday,
+    Sunday,
+}
+
+impl WeekStart {
+    /// Every week start, in the order they are offered in the settings.
+    pub const ALL: [Self; 3] = [Self::Locale, Self::Monday, Self::Sunday];
+
+    /// Regions where the week is usually written starting on Sunday, by their ISO 3166 code.
+    const SUNDAY_REGIONS: [&str; 23] = [
+        "BR", "CA", "CO", "DO", "GT", "HK", "HN", "IL", "IN", "JP", "KR", "MX", "NI", "PA", "PE", "PH", "PR",
+        "SA", "SV", "TW", "US", "VE", "ZA",
+    ];
+
+    /// The first day of the week in the region of the locale times are formatted for, going by
+    /// `LC_ALL`, `LC_TIME` and `LANG` like the C library does. Monday when that can't be told.
+    fn from_locale() -> Self {
+        let locale = ["LC_ALL", "LC_TIME", "LANG"]
+            .into_iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+        Self::for_locale(&locale)
+    }
+
+    /// The first day of the week in the region of `locale`, Monday when it has none.
+    fn for_locale(locale: &str) -> Self {
+        // Locales look like `en_US.UTF-8` or `de_DE@euro`
+        let region = locale.split(['.', '@']).next().and_then(|name| name.split_once('_')).map(|(_, region)| region);
+        match region {
+            Some(region) if Self::SUNDAY_REGIONS.contains(&region) => Self::Sunday,
+            _ => Self::Monday,
+        }
+    }
+
+    /// The first day of the week, going by the current locale for [`WeekStart::Locale`].
+    pub fn weekday(self) -> Weekday {
+        match self {
+            Self::Locale => Self::from_locale().weekday(),
+            Self::Monday => Weekday::Mon,
+            Self::Sunday => Weekday::Sun,
+        }
+    }
+}
+
+/// Which way the clock is laid out.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Orientation {
+    /// Fields side by side, with bits running top to bottom. Suits horizontal panels.
+    #[default]
+    Horizontal,
+    /// Fields stacked on top of each other, with bits running left to right. Suits vertical panels.
+    Vertical,
+}
+
+/// The settings of the panel's own clock applet that this one follows.
+#[derive(Debug, Default, Clone, CosmicConfigEntry, PartialEq, Serialize, Deserialize)]
 #[version = 1]
+#[serde(default)]
+pub struct SystemTimeConfig {
+    /// Show the time in twenty-four hours.
+    pub military_time: bool,
+}
+
+/// The settings of the applet.
+///
+/// Version 2 dropped the fixed UTC offset of version 1 in favour of a time zone.
+#[derive(Debug, Clone, CosmicConfigEntry, PartialEq, Serialize, Deserialize)]
+#[version = 2]
+#[serde(default)]
 pub struct Config {
-    demo: String,
+    /// The encoding used to draw the clock.
+    pub mode: DisplayMode,
+    /// Which way the columns and bits are laid out.
+    pub orientation: Orientation,
+    /// Where the columns sit when there is more room across than they fill.
+    pub alignment: ClockAlignment,
+    /// The shape each bit is drawn as.
+    pub cell_shape: CellShape,
+    /// Gap between neighbouring cells, from 0 (touching) to 1 (a full radius apart).
+    pub cell_spacing: f32,
+    /// Extra gap between the hours, minutes and seconds, from 0 to 2 radii.
+    pub group_gap: f32,
+    /// How much larger or smaller than fits the panel the circles are drawn, from 0.5 to 2.
+    pub scale: f32,
+    /// Share of the panel's thickness left empty above and below the circles, from 0 to 0.6.
+    pub padding: f32,
+    /// Room left empty before the first column and after the last, in logical pixels.
+    pub side_padding: f32,
+    /// How bits that are off are drawn.
+    pub inactive_style: InactiveStyle,
+    /// Which end of a column the most significant bit is drawn at.
+    pub bit_order: BitOrder,
+    /// Show hours as 1-12 with an AM/PM indicator instead of 0-23.
+    pub twelve_hour: bool,
+    /// Use the desktop's time format instead of `twelve_hour`.
+    pub follow_system_hour_format: bool,
+    /// How the AM/PM indicator is drawn in twelve hour mode.
+    pub meridiem_style: MeridiemStyle,
+    /// The IANA name of the time zone the clock is shown in, such as `Europe/Amsterdam`.
+    /// The system's own zone is used when this is empty or not a known zone.
+    pub timezone: String,
+    /// Time zones for the panels on particular displays, by the name of the display's output
+    /// (such as `DP-1`), in place of `timezone`. There is no setting for this in the popup.
+    pub output_timezones: BTreeMap<String, String>,
+    /// The IANA name of a second time zone, shown in a clock of its own next to the first.
+    pub secondary_zone: Option<String>,
+    /// Draw the seconds columns, rather than only hours and minutes.
+    pub show_seconds: bool,
+    /// Draw the seconds as a ring that fills up over the minute, in place of their columns.
+    pub seconds_as_ring: bool,
+    /// Add a column for tenths of a second after the seconds.
+    pub show_deciseconds: bool,
+    /// Lay the columns out the other way around, with the seconds first and the hours last.
+    pub mirror: bool,
+    /// Separate the hour, minute and second columns with thin lines.
+    pub show_labels: bool,
+    /// Blink a colon between the hours and minutes every second.
+    pub blink_separator: bool,
+    /// Show the date in the popup, below the time.
+    pub show_date: bool,
+    /// Pulse the lowest bit every second.
+    pub heartbeat: bool,
+    /// How bright lit circles are, from 0 (dimmed, but still visible) to 1 (full color).
+    pub brightness: f32,
+    /// Leave out the tens of hours column while it is zero, in binary coded decimal mode.
+    pub blank_leading_zero: bool,
+    /// Draw a rounded panel behind the circles.
+    pub show_background: bool,
+    /// Write the value of each column below it.
+    pub show_digit_labels: bool,
+    /// Show the day of the month in binary in the popup.
+    pub show_day_of_month: bool,
+    /// Show a strip of seven circles below the clock, one for every day of the week, with today's lit.
+    pub show_weekday: bool,
+    /// Write the initials of the days in the weekday strip.
+    pub label_weekdays: bool,
+    /// The day the weekday strip starts with, the usual one for the locale by default.
+    pub week_starts_on: WeekStart,
+    /// Show a thin bar below the clock, filled as far as the day has passed.
+    pub show_day_progress: bool,
+    /// Show only the hours and minutes in the panel, with the full clock in the popup.
+    pub compact_panel: bool,
+    /// Dim the clock as far as it goes while the session is idle, and stop it ticking every second.
+    pub dim_when_idle: bool,
+    /// Dim the clock as far as it goes during the night.
+    pub night_mode: bool,
+    /// The hour the night starts at, from 0 to 23.
+    pub night_start: u32,
+    /// The hour the night ends at, from 0 to 23. Earlier than `night_start` when the night crosses midnight.
+    pub night_end: u32,
+    /// Tint each row with its own hue, so the place value of every bit stands out.
+    pub place_value_tint: bool,
+    /// Write the place value of every bit faintly inside its circle.
+    pub show_place_values: bool,
+    /// Draw in pure black and white with heavier outlines, over the theme and any chosen colors.
+    pub high_contrast: bool,
+    /// Swap the lit and unlit colors, so lit bits are the darker ones on a bright background.
+    pub invert: bool,
+    /// Outline lit cells in a contrasting color.
+    pub cell_border: bool,
+    /// Briefly brighten the whole clock at the start of every minute.
+    pub minute_pulse: bool,
+    /// Send a notification at the start of every hour.
+    pub hourly_chime: bool,
+    /// The time of day, as `HH:MM` or `HH:MM:SS`, to send a notification and flash the clock at.
+    pub alarm_time: Option<String>,
+    /// Fade bits in and out when they change, rather than switching them instantly.
+    pub animations: bool,
+    /// Fixed `#rrggbb` or `#rrggbbaa` color for lit circles. The theme's accent color is used when unset.
+    pub active_color: Option<String>,
+    /// Fixed `#rrggbb` or `#rrggbbaa` color for unlit circles. A muted theme color is used when unset.
+    pub inactive_color: Option<String>,
+    /// Give the hours, minutes and seconds lit colors of their own.
+    pub per_field_colors: bool,
+    /// Fixed `#rrggbb` or `#rrggbbaa` colors for the hours, minutes and seconds, in that order. Variations on the
+    /// active color are used for the ones that are unset.
+    #[serde(with = "optional_colors")]
+    pub field_colors: [Option<String>; 3],
+    /// Blend the lit color of every column from the least to the most significant bit.
+    pub gradient: bool,
+    /// Fixed `#rrggbb` or `#rrggbbaa` color for the least significant bits in the gradient. The active
+    /// color is used when unset.
+    pub gradient_start: Option<String>,
+    /// Fixed `#rrggbb` or `#rrggbbaa` color for the most significant bits in the gradient. A variation
+    /// on the start color is used when unset.
+    pub gradient_end: Option<String>,
+    /// Preferred width of the settings popup, in logical pixels.
+    pub popup_width: u32,
+    /// Preferred largest height of the settings popup, in logical pixels. It scrolls beyond that.
+    pub popup_height: u32,
+    /// How many low bits of the Unix timestamp epoch mode shows, from 8 to 32.
+    pub epoch_bits: u32,
+    /// How long the countdown runs for, in minutes, from 1 to 99.
+    pub countdown_minutes: u32,
+    /// How often the clock ticks, in milliseconds, in place of the interval that suits what it shows.
+    /// There is no setting for this in the popup.
+    pub tick_interval_ms: Option<u32>,
+    /// An RFC 3339 timestamp, such as `2025-11-25T09:49:09+01:00`, to show instead of the current
+    /// time. There is no setting for this in the popup; it exists to make screenshots and tests
+    /// repeatable.
+    pub frozen_time: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            mode: DisplayMode::default(),
+            orientation: Orientation::default(),
+            alignment: ClockAlignment::default(),
+            cell_shape: CellShape::default(),
+            cell_spacing: 0.0,
+            group_gap: 0.0,
+            scale: 1.0,
+            padding: crate::clock::PADDING_RATIO,
+            side_padding: 0.0,
+            inactive_style: InactiveStyle::default(),
+            bit_order: BitOrder::default(),
+            twelve_hour: false,
+            follow_system_hour_format: false,
+            meridiem_style: MeridiemStyle::default(),
+            timezone: String::new(),
+            secondary_zone: None,
+            show_seconds: true,
+            seconds_as_ring: false,
+            show_deciseconds: false,
+            mirror: false,
+            show_labels: false,
+            blink_separator: false,
+            show_date: false,
+            heartbeat: false,
+            minute_pulse: false,
+            hourly_chime: false,
+            alarm_time: None,
+            animations: false,
+            place_value_tint: false,
+            show_place_values: false,
+            high_contrast: false,
+            invert: false,
+            cell_border: false,
+            brightness: 1.0,
+            blank_leading_zero: false,
+            show_background: false,
+            show_digit_labels: false,
+            show_day_of_month: false,
+            show_weekday: false,
+            label_weekdays: true,
+            week_starts_on: WeekStart::Locale,
+            show_day_progress: false,
+            compact_panel: false,
+            dim_when_idle: false,
+            night_mode: false,
+            night_start: 22,
+            night_end: 6,
+            active_color: None,
+            inactive_color: None,
+            per_field_colors: false,
+            field_colors: [None, None, None],
+            gradient: false,
+            gradient_start: None,
+            gradient_end: None,
+            popup_width: 372,
+            popup_height: 1080,
+            epoch_bits: 24,
+            countdown_minutes: 25,
+            tick_interval_ms: None,
+            frozen_time: None,
+            output_timezones: BTreeMap::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Whether the clock changes every second, rather than only every minute.
+    pub fn needs_second_ticks(&self) -> bool {
+        // A hexadecimal time unit is about a second and a third long
+        self.show_seconds
+            || self.blink_separator
+            || matches!(
+                self.mode,
+                DisplayMode::BinarySeconds
+                    | DisplayMode::HEX
+                    | DisplayMode::Stopwatch
+                    | DisplayMode::UnixEpoch
+                    | DisplayMode::Countdown
+            )
+    }
+
+    /// Whether the clock shows tenths of a second, which it only does next to seconds.
+    pub fn shows_deciseconds(&self) -> bool {
+        let seconds = match self.mode {
+            DisplayMode::BinarySeconds | DisplayMode::Stopwatch | DisplayMode::Countdown => true,
+            DisplayMode::HEX | DisplayMode::UnixEpoch => false,
+            _ => self.show_seconds,
+        };
+        self.show_deciseconds && seconds
+    }
+
+    /// How often the clock ticks, in milliseconds: the configured interval if there is one, or else
+    /// just often enough to keep up with what it shows.
+    pub fn tick_period_ms(&self) -> u32 {
+        if let Some(interval) = self.tick_interval_ms {
+            // Ticks any closer together only keep the CPU busy redrawing the same thing
+            interval.max(MIN_TICK_INTERVAL_MS)
+        } else if self.shows_deciseconds() {
+            100
+        } else if self.needs_second_ticks() {
+            1000
+        } else {
+            60_000
+        }
+    }
+
+    /// Whether `hour` falls in the night, when night mode is on.
+    pub fn is_night(&self, hour: u32) -> bool {
+        if !self.night_mode {
+            return false;
+        }
+        if self.night_start <= self.night_end {
+            (self.night_start..self.night_end).contains(&hour)
+        } else {
+            // The night crosses midnight, say from 22:00 to 06:00
+            hour >= self.night_start || hour < self.night_end
+        }
+    }
+
+    /// The time the clock is frozen at, if it is frozen and the timestamp is valid.
+    pub fn frozen_time(&self) -> Option<DateTime<FixedOffset>> {
+        DateTime::parse_from_rfc3339(self.frozen_time.as_deref()?).ok()
+    }
+
+    /// The time of day the alarm goes off at, if one is set and it is a valid time.
+    pub fn alarm_time(&self) -> Option<NaiveTime> {
+        parse_alarm_time(self.alarm_time.as_deref()?)
+    }
+
+    /// The configured time zone, or `None` to follow the system's zone.
+    pub fn timezone(&self) -> Option<Tz> {
+        self.timezone.parse().ok()
+    }
+
+    /// The time zone for the panel on `output`, which is the configured zone unless that
+    /// output has one of its own.
+    pub fn timezone_for(&self, output: &str) -> Option<Tz> {
+        match self.output_timezones.get(output) {
+            Some(zone) => zone.parse().ok(),
+            None => self.timezone(),
+        }
+    }
+
+    /// How many low bits of the Unix timestamp to show in epoch mode, clamped to the range on offer.
+    pub fn epoch_bits(&self) -> u8 {
+        self.epoch_bits.clamp(EPOCH_BITS_RANGE.0, EPOCH_BITS_RANGE.1) as u8
+    }
+
+    /// How long the countdown runs for, clamped to the range on offer.
+    pub fn countdown(&self) -> Duration {
+        let minutes = self.countdown_minutes.clamp(COUNTDOWN_MINUTES_RANGE.0, COUNTDOWN_MINUTES_RANGE.1);
+        Duration::from_secs(u64::from(minutes) * 60)
+    }
+
+    /// The preferred popup width and height, clamped to sizes that still fit the settings and a screen.
+    pub fn popup_size(&self) -> (f32, f32) {
+        let width = self.popup_width.clamp(POPUP_WIDTH_RANGE.0, POPUP_WIDTH_RANGE.1);
+        let height = self.popup_height.clamp(POPUP_HEIGHT_RANGE.0, POPUP_HEIGHT_RANGE.1);
+        (width as f32, height as f32)
+    }
+
+    /// The second time zone to show, if one is set and it is a known zone.
+    pub fn secondary_zone(&self) -> Option<Tz> {
+        self.secondary_zone.as_deref()?.parse().ok()
+    }
+
+    /// Loads the settings saved in `context`, for this version of the config.
+    ///
+    /// Until anything has been saved for this version, the settings saved for the version before
+    /// it in `previous` are upgraded instead, and saved to `context` so that only happens once.
+    /// Settings that can't be read keep their defaults rather than losing the rest.
+    pub fn load(context: &cosmic_config::Config, previous: Option<&cosmic_config::Config>) -> Self {
+        if !is_saved_in(context) {
+            if let Some(previous) = previous.filter(|previous| is_saved_in(previous)) {
+                let mut config = Self::read(previous);
+                config.upgrade_from_v1(previous);
+                if let Err(why) = config.write_entry(context) {
+                    eprintln!("error saving upgraded config: {why}");
+                }
+                return config;
+            }
+        }
+        Self::read(context)
+    }
+
+    /// The settings saved in `context`, with the defaults for any that can't be read.
+    fn read(context: &cosmic_config::Config) -> Self {
+        match Self::get_entry(context) {
+            Ok(config) => config,
+            Err((errors, config)) => {
+                for why in errors {
+                    eprintln!("error loading config: {why}");
+                }
+                config
+            }
+        }
+    }
+
+    /// Converts the settings of version 1 in `previous`, which this config was just read from, to
+    /// the ones that replaced them in version 2. The rest kept their names and meaning.
+    fn upgrade_from_v1(&mut self, previous: &cosmic_config::Config) {
+        let Ok(offset) = previous.get::<i32>(LEGACY_OFFSET_KEY) else {
+            return;
+        };
+        // The offset defaulted to the system's own, which is best kept following daylight saving
+        if offset == 0 || offset == Local::now().offset().local_minus_utc() || !self.timezone.is_empty() {
+            return;
+        }
+        match legacy_offset_zone(offset) {
+            Some(zone) => self.timezone = zone,
+            None => eprintln!("no time zone for the old UTC offset of {offset} seconds, using the system's"),
+        }
+    }
+
+    /// The settings as a TOML document, to share or keep outside of COSMIC's own config.
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string_pretty(self)
+    }
+
+    /// Reads settings written by [`Config::to_toml`].
+    ///
+    /// Settings missing from `text` are left at their defaults, and so are any that don't hold
+    /// a value the setting can take. The names of the latter are returned alongside.
+    pub fn from_toml(text: &str) -> Result<(Self, Vec<String>), toml::de::Error> {
+        let imported: toml::Table = text.parse()?;
+        let mut merged = toml::Table::try_from(Self::default()).unwrap_or_default();
+        let mut ignored = Vec::new();
+        // One at a time, so a bad value only costs its own setting
+        for (key, value) in imported {
+            let previous = merged.insert(key.clone(), value);
+            if toml::Value::Table(merged.clone()).try_into::<Self>().is_err() {
+                match previous {
+                    Some(previous) => merged.insert(key.clone(), previous),
+                    None => merged.remove(&key),
+                };
+                ignored.push(key);
+            }
+        }
+        Ok((toml::Value::Table(merged).try_into()?, ignored))
+    }
+
+    /// Drops color overrides that aren't valid colors, so the theme's colors are used instead.
+    pub fn discard_invalid_colors(&mut self) {
+        let [hour, minute, second] = &mut self.field_colors;
+        let colors = [
+            &mut self.active_color,
+            &mut self.inactive_color,
+            hour,
+            minute,
+            second,
+            &mut self.gradient_start,
+            &mut self.gradient_end,
+        ];
+        for color in colors {
+            if color.as_deref().is_some_and(|hex| parse_hex_color(hex).is_none()) {
+                eprintln!("ignoring invalid color in config: {color:?}");
+                *color = None;
+            }
+        }
+    }
+
+    /// The color override for lit circles, if one is set.
+    pub fn active_color(&self) -> Option<Color> {
+        self.active_color.as_deref().and_then(parse_hex_color)
+    }
+
+    /// The color override for unlit circles, if one is set.
+    pub fn inactive_color(&self) -> Option<Color> {
+        self.inactive_color.as_deref().and_then(parse_hex_color)
+    }
+
+    /// The color overrides for the hours, minutes and seconds, or `None` when they share one color.
+    pub fn field_colors(&self) -> Option<[Option<Color>; 3]> {
+        self.per_field_colors
+            .then(|| self.field_colors.each_ref().map(|color| color.as_deref().and_then(parse_hex_color)))
+    }
+
+    /// The color overrides for the start and end of the gradient, or `None` when there is no gradient.
+    pub fn gradient_colors(&self) -> Option<[Option<Color>; 2]> {
+        self.gradient.then(|| {
+            [&self.gradient_start, &self.gradient_end].map(|color| color.as_deref().and_then(parse_hex_color))
+        })
+    }
+}
+
+/// Formats a color as `#rrggbb`, or as `#rrggbbaa` when it is at all transparent.
+pub fn format_hex_color(color: Color) -> String {
+    let [r, g, b, a] = color.into_rgba8();
+    if a == u8::MAX {
+        format!("#{r:02x}{g:02x}{b:02x}")
+    } else {
+        format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+    }
+}
+
+/// Whether settings were ever saved in `context`. Every save writes all of them, the display mode
+/// included, so an unreadable mode counts as nothing saved.
+fn is_saved_in(context: &cosmic_config::Config) -> bool {
+    context.get::<DisplayMode>("mode").is_ok()
+}
+
+/// The `Etc/GMT` zone for a whole hour offset from UTC, in seconds east of Greenwich.
+fn legacy_offset_zone(offset: i32) -> Option<String> {
+    if offset % 3600 != 0 || !(-12..=14).contains(&(offset / 3600)) {
+        return None;
+    }
+    // These zones are named the POSIX way round, with the sign flipped: UTC+2 is `Etc/GMT-2`
+    Some(format!("Etc/GMT{:+}", -offset / 3600).replace("GMT+0", "GMT"))
+}
+
+/// Parses an `HH:MM` or `HH:MM:SS` time of day, as the alarm is set in.
+pub fn parse_alarm_time(text: &str) -> Option<NaiveTime> {
+    let text = text.trim();
+    NaiveTime::parse_from_str(text, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(text, "%H:%M"))
+        .ok()
+}
+
+/// Parses a `#rrggbb` or `#rrggbbaa` color, with or without the leading `#`.
+pub fn parse_hex_color(hex: &str) -> Option<Color> {
+    let hex = hex.trim().trim_start_matches('#');
+    if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |range: std::ops::Range<usize>| u8::from_str_radix(&hex[range], 16).ok();
+    let alpha = if hex.len() == 8 { channel(6..8)? } else { u8::MAX };
+    Some(Color::from_rgba8(channel(0..2)?, channel(2..4)?, channel(4..6)?, f32::from(alpha) / 255.0))
+}
+
+/// TOML has no way to leave a gap in an array, so unset field colors are written as empty strings.
+mod optional_colors {
+    use super::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(colors: &[Option<String>; 3], serializer: S) -> Result<S::Ok, S::Error> {
+        colors.each_ref().map(|color| color.as_deref().unwrap_or_default()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[Option<String>; 3], D::Error> {
+        let colors = <[String; 3]>::deserialize(deserializer)?;
+        Ok(colors.map(|color| Some(color).filter(|color| !color.is_empty())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmic::cosmic_config::ConfigSet;
+    use std::path::PathBuf;
+
+    #[test]
+    fn week_start_follows_the_locale_region() {
+        assert_eq!(WeekStart::for_locale("en_US.UTF-8"), WeekStart::Sunday);
+        assert_eq!(WeekStart::for_locale("ja_JP"), WeekStart::Sunday);
+        assert_eq!(WeekStart::for_locale("de_DE@euro"), WeekStart::Monday);
+        assert_eq!(WeekStart::for_locale("en_GB.UTF-8"), WeekStart::Monday);
+        // Without a region there is nothing to go by
+        assert_eq!(WeekStart::for_locale(""), WeekStart::Monday);
+        assert_eq!(WeekStart::for_locale("C.UTF-8"), WeekStart::Monday);
+        assert_eq!(WeekStart::Sunday.weekday(), Weekday::Sun);
+        // The default is resolved when shown, so it isn't fixed to the locale it was first saved in
+        assert_eq!(Config::default().week_starts_on, WeekStart::Locale);
+    }
+
+    #[test]
+    fn legacy_offsets_map_to_etc_zones() {
+        assert_eq!(legacy_offset_zone(2 * 3600).as_deref(), Some("Etc/GMT-2"));
+        assert_eq!(legacy_offset_zone(-5 * 3600).as_deref(), Some("Etc/GMT+5"));
+        assert_eq!(legacy_offset_zone(14 * 3600).as_deref(), Some("Etc/GMT-14"));
+        assert_eq!(legacy_offset_zone(-12 * 3600).as_deref(), Some("Etc/GMT+12"));
+        assert_eq!(legacy_offset_zone(0).as_deref(), Some("Etc/GMT"));
+        // Only whole hours within the range of real offsets have a zone
+        assert_eq!(legacy_offset_zone(5 * 3600 + 1800), None);
+        assert_eq!(legacy_offset_zone(-(9 * 3600 + 2700)), None);
+        assert_eq!(legacy_offset_zone(15 * 3600), None);
+        // Every zone named is one chrono-tz knows
+        for hours in -12..=14 {
+            let zone = legacy_offset_zone(hours * 3600).unwrap();
+            assert!(zone.parse::<Tz>().is_ok(), "{zone} is not a known zone");
+        }
+    }
+
+    /// A directory of its own under the temp dir, removed again even when the test fails.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("binary-clock-{name}-{}", std::process::id()));
+            std::fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            std::fs::remove_dir_all(&self.0).ok();
+        }
+    }
+
+    #[test]
+    fn version_1_settings_are_upgraded() {
+        // An offset other than the system's own, which would just be dropped
+        let offset = if Local::now().offset().local_minus_utc() == 2 * 3600 { 3 * 3600 } else { 2 * 3600 };
+        let expected = legacy_offset_zone(offset).unwrap();
+
+        // Opened in a directory of the test's own, so the real settings and environment are left alone
+        let home = TempDir::new("upgrade");
+        let app_id = "com.example.BinaryClockUpgradeTest";
+        let previous = cosmic_config::Config::with_custom_path(app_id, 1, home.0.clone()).unwrap();
+        let context = cosmic_config::Config::with_custom_path(app_id, Config::VERSION, home.0.clone()).unwrap();
+        previous.set(LEGACY_OFFSET_KEY, offset).unwrap();
+        previous.set("show_seconds", false).unwrap();
+        previous.set("mode", DisplayMode::BinaryHMS).unwrap();
+
+        let config = Config::load(&context, Some(&previous));
+        assert_eq!(config.timezone, expected);
+        // Settings that didn't change meaning come through as they were
+        assert!(!config.show_seconds);
+        assert_eq!(config.mode, DisplayMode::BinaryHMS);
+        // Saved in the new version, which is read from then on
+        assert_eq!(Config::load(&context, None), config);
+        previous.set("show_seconds", true).unwrap();
+        assert_eq!(Config::load(&context, Some(&previous)), config);
+    }
+
+    #[test]
+    fn nothing_saved_loads_the_defaults() {
+        let home = TempDir::new("defaults");
+        let app_id = "com.example.BinaryClockDefaultsTest";
+        let previous = cosmic_config::Config::with_custom_path(app_id, 1, home.0.clone()).unwrap();
+        let context = cosmic_config::Config::with_custom_path(app_id, Config::VERSION, home.0.clone()).unwrap();
+        assert_eq!(Config::load(&context, Some(&previous)), Config::default());
+    }
 }
@@ -0,0 +1,21 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Saves pictures of the clock as PNG files.
+
+use chrono::{DateTime, FixedOffset};
+use cosmic::iced::window::Screenshot;
+use std::path::Path;
+
+/// The name suggested for a picture taken at `time`. The user picks where it goes.
+pub fn file_name(time: DateTime<FixedOffset>) -> String {
+    format!("binary-clock-{}.png", time.format("%Y-%m-%d-%H%M%S"))
+}
+
+/// Encodes the RGBA pixels of `screenshot` as a PNG and writes it to `path`.
+pub fn write_png(path: &Path, screenshot: &Screenshot) -> Result<(), png::EncodingError> {
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let mut encoder = png::Encoder::new(file, screenshot.size.width, screenshot.size.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(&screenshot.bytes)
+}
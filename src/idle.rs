@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Follows whether the session is idle, going by the screensaver turning on and off.
+
+use cosmic::iced::Subscription;
+use futures_util::{SinkExt, StreamExt};
+
+/// Emits `true` when the screensaver turns on and `false` when it turns off again.
+///
+/// Sessions without a screensaver service on the bus never emit anything, so the clock
+/// simply never counts as idle there.
+pub fn subscription() -> Subscription<bool> {
+    struct Idle;
+
+    Subscription::run_with_id(
+        std::any::TypeId::of::<Idle>(),
+        cosmic::iced::stream::channel(1, |mut channel| async move {
+            if let Err(why) = watch(&mut channel).await {
+                eprintln!("error watching for idle: {why}");
+            }
+            futures_util::future::pending().await
+        }),
+    )
+}
+
+async fn watch(channel: &mut cosmic::iced::futures::channel::mpsc::Sender<bool>) -> zbus::Result<()> {
+    let connection = zbus::Connection::session().await?;
+    let proxy = zbus::Proxy::new(
+        &connection,
+        "org.freedesktop.ScreenSaver",
+        "/org/freedesktop/ScreenSaver",
+        "org.freedesktop.ScreenSaver",
+    )
+    .await?;
+    let mut changes = proxy.receive_signal("ActiveChanged").await?;
+    while let Some(change) = changes.next().await {
+        let active: bool = change.body().deserialize()?;
+        if channel.send(active).await.is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,9 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! A binary clock for COSMIC.
+//!
+//! The panel applet is built on [`clock::BinaryClock`], which other COSMIC applications can show
+//! in their own views as well. See `examples/embed.rs`.
+
+pub mod clock;
+pub mod config;
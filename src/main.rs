@@ -1,8 +1,12 @@
 // SPDX-License-Identifier: MPL-2.0
 
 mod app;
-mod config;
+mod export;
 mod i18n;
+mod idle;
+mod motion;
+#[cfg(feature = "status-file")]
+mod status;
 
 fn main() -> cosmic::iced::Result {
     // Get the system's preferred languages.
@@ -11,6 +15,14 @@ fn main() -> cosmic::iced::Result {
     // Enable localizations to be applied.
     i18n::init(&requested_languages);
 
-    // Starts the applet's event loop with `()` as the application's flags.
-    cosmic::applet::run::<app::AppModel>(())
+    let flags = app::Flags::from_args();
+
+    if flags.windowed {
+        // Starts a regular window showing a large clock.
+        let settings = cosmic::app::Settings::default().size(cosmic::iced::Size::new(480.0, 240.0));
+        cosmic::app::run::<app::AppModel>(settings, flags)
+    } else {
+        // Starts the applet's event loop.
+        cosmic::applet::run::<app::AppModel>(flags)
+    }
 }
@@ -0,0 +1,59 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Follows the desktop's preference for reduced motion, as shared through the settings portal.
+
+use cosmic::iced::Subscription;
+use futures_util::{SinkExt, StreamExt};
+use zbus::zvariant::OwnedValue;
+
+const NAMESPACE: &str = "org.freedesktop.appearance";
+const KEY: &str = "reduced-motion";
+
+/// Emits whether reduced motion is asked for once at startup, and again whenever that changes.
+///
+/// Portals too old to know the setting never emit anything, so motion is left on there.
+pub fn subscription() -> Subscription<bool> {
+    struct ReducedMotion;
+
+    Subscription::run_with_id(
+        std::any::TypeId::of::<ReducedMotion>(),
+        cosmic::iced::stream::channel(1, |mut channel| async move {
+            if let Err(why) = watch(&mut channel).await {
+                eprintln!("error watching for reduced motion: {why}");
+            }
+            futures_util::future::pending().await
+        }),
+    )
+}
+
+async fn watch(channel: &mut cosmic::iced::futures::channel::mpsc::Sender<bool>) -> zbus::Result<()> {
+    let connection = zbus::Connection::session().await?;
+    let proxy = zbus::Proxy::new(
+        &connection,
+        "org.freedesktop.portal.Desktop",
+        "/org/freedesktop/portal/desktop",
+        "org.freedesktop.portal.Settings",
+    )
+    .await?;
+    // Subscribed to first, so a change made while reading the current value isn't missed
+    let mut changes = proxy.receive_signal("SettingChanged").await?;
+    let current: OwnedValue = proxy.call("ReadOne", &(NAMESPACE, KEY)).await?;
+    if channel.send(is_reduced(current)?).await.is_err() {
+        return Ok(());
+    }
+    while let Some(change) = changes.next().await {
+        let (namespace, key, value): (String, String, OwnedValue) = change.body().deserialize()?;
+        if namespace != NAMESPACE || key != KEY {
+            continue;
+        }
+        if channel.send(is_reduced(value)?).await.is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Reads the setting's value, which is 1 for reduced motion and 0 for no preference.
+fn is_reduced(value: OwnedValue) -> zbus::Result<bool> {
+    Ok(u32::try_from(value)? == 1)
+}
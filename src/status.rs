@@ -0,0 +1,34 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Publishes what the clock shows to a file, for scripts and tests that want to check on it.
+//!
+//! Only built with the `status-file` feature.
+
+use chrono::{DateTime, FixedOffset};
+use example::config::DisplayMode;
+use std::path::PathBuf;
+
+/// The file the status is written to, in the user's runtime directory.
+fn path() -> Option<PathBuf> {
+    let runtime_dir = std::env::var_os("XDG_RUNTIME_DIR")?;
+    Some(PathBuf::from(runtime_dir).join("binary-clock-status"))
+}
+
+/// Writes the displayed time and display mode as `key=value` lines:
+///
+/// ```text
+/// time=2025-11-25T09:49:09+01:00
+/// mode=BCD
+/// ```
+///
+/// The file is replaced in one go, so readers never see half of an update.
+pub fn publish(time: DateTime<FixedOffset>, mode: DisplayMode) {
+    let Some(path) = path() else {
+        return;
+    };
+    let contents = format!("time={}\nmode={mode:?}\n", time.to_rfc3339_opts(chrono::SecondsFormat::Secs, false));
+    let temporary = path.with_extension("tmp");
+    if let Err(why) = std::fs::write(&temporary, contents).and_then(|()| std::fs::rename(&temporary, &path)) {
+        eprintln!("error writing status file {}: {why}", path.display());
+    }
+}